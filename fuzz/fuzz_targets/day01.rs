@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+#[path = "../../src/bin/day01.rs"]
+#[allow(dead_code)]
+mod day01;
+
+fuzz_target!(|data: &[u8]| {
+    let lines = String::from_utf8_lossy(data).lines().map(|s| s.to_string()).collect();
+    if let Ok(input) = day01::process_input(lines) {
+        let params = day01::Params::default();
+        day01::part1(&input, &params);
+        day01::part2(&input, &params);
+    }
+});
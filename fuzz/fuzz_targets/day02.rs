@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+#[path = "../../src/bin/day02.rs"]
+#[allow(dead_code)]
+mod day02;
+
+fuzz_target!(|data: &[u8]| {
+    let lines = String::from_utf8_lossy(data).lines().map(|s| s.to_string()).collect();
+    if let Ok(input) = day02::process_input(lines) {
+        let params = day02::Params::default();
+        day02::part1(&input, &params);
+        day02::part2(&input, &params);
+    }
+});
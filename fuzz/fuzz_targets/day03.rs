@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+#[path = "../../src/bin/day03.rs"]
+#[allow(dead_code)]
+mod day03;
+
+fuzz_target!(|data: &[u8]| {
+    let lines = String::from_utf8_lossy(data).lines().map(|s| s.to_string()).collect();
+    if let Ok(input) = day03::process_input(lines) {
+        let params = day03::Params::default();
+        day03::part1(&input, &params);
+        day03::part2(&input, &params);
+    }
+});
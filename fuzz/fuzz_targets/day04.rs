@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+#[path = "../../src/bin/day04.rs"]
+#[allow(dead_code)]
+mod day04;
+
+fuzz_target!(|data: &[u8]| {
+    let lines = String::from_utf8_lossy(data).lines().map(|s| s.to_string()).collect();
+    if let Ok(input) = day04::process_input(lines) {
+        let params = day04::Params::default();
+        day04::part1(&input, &params);
+        day04::part2(&input, &params);
+    }
+});
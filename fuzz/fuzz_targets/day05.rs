@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+#[path = "../../src/bin/day05.rs"]
+#[allow(dead_code)]
+mod day05;
+
+fuzz_target!(|data: &[u8]| {
+    let lines = String::from_utf8_lossy(data).lines().map(|s| s.to_string()).collect();
+    if let Ok(input) = day05::process_input(lines) {
+        let params = day05::Params::default();
+        day05::part1(&input, &params);
+        day05::part2(&input, &params);
+    }
+});
@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+#[path = "../../src/bin/day06.rs"]
+#[allow(dead_code)]
+mod day06;
+
+fuzz_target!(|data: &[u8]| {
+    let lines = String::from_utf8_lossy(data).lines().map(|s| s.to_string()).collect();
+    if let Ok(input) = day06::process_input(lines) {
+        let params = day06::Params::default();
+        let _ = day06::part1(&input, &params);
+        let _ = day06::part2(&input, &params);
+    }
+});
@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+#[path = "../../src/bin/day07.rs"]
+#[allow(dead_code)]
+mod day07;
+
+fuzz_target!(|data: &[u8]| {
+    let lines = String::from_utf8_lossy(data).lines().map(|s| s.to_string()).collect();
+    if let Ok(input) = day07::process_input(lines) {
+        let params = day07::Params::default();
+        day07::part1(&input, &params);
+        let _ = day07::part2(&input, &params);
+    }
+});
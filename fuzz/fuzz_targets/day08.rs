@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+#[path = "../../src/bin/day08.rs"]
+#[allow(dead_code)]
+mod day08;
+
+fuzz_target!(|data: &[u8]| {
+    let lines = String::from_utf8_lossy(data).lines().map(|s| s.to_string()).collect();
+    if let Ok(input) = day08::process_input(lines) {
+        let params = day08::Params::default();
+        day08::part1(&input, &params);
+        day08::part2(&input);
+    }
+});
@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+#[path = "../../src/bin/day09.rs"]
+#[allow(dead_code)]
+mod day09;
+
+fuzz_target!(|data: &[u8]| {
+    let lines = String::from_utf8_lossy(data).lines().map(|s| s.to_string()).collect();
+    if let Ok(input) = day09::process_input(lines) {
+        let params = day09::Params::default();
+        day09::part1(&input, &params);
+        day09::part2(&input, &params);
+    }
+});
@@ -0,0 +1,20 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+#[path = "../../src/bin/day10.rs"]
+#[allow(dead_code)]
+mod day10;
+
+fuzz_target!(|data: &[u8]| {
+    let lines = String::from_utf8_lossy(data).lines().map(|s| s.to_string()).collect();
+    if let Ok(input) = day10::process_input(lines) {
+        let params = day10::Params::default();
+        // Part 1 tries every combination of buttons
+        if input.iter().any(|(_, buttons, _)| buttons.len() > 16) {
+            return;
+        }
+        day10::part1(&input, &params);
+        day10::part2(&input, &params);
+    }
+});
@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+#[path = "../../src/bin/day11.rs"]
+#[allow(dead_code)]
+mod day11;
+
+fuzz_target!(|data: &[u8]| {
+    let lines = String::from_utf8_lossy(data).lines().map(|s| s.to_string()).collect();
+    if let Ok(input) = day11::process_input(lines) {
+        let params = day11::Params::default();
+        let _ = day11::part1(&input, &params);
+        let _ = day11::part2(&input, &params);
+    }
+});
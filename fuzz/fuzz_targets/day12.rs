@@ -0,0 +1,14 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+#[path = "../../src/bin/day12.rs"]
+#[allow(dead_code)]
+mod day12;
+
+fuzz_target!(|data: &[u8]| {
+    let lines = String::from_utf8_lossy(data).lines().map(|s| s.to_string()).collect();
+    if let Ok(input) = day12::process_input(lines) {
+        let _ = day12::part1(&input, &day12::Params::default());
+    }
+});
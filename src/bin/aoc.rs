@@ -0,0 +1,34 @@
+use std::io::{BufWriter, Write, stdout};
+
+use aoc2025::common::Args;
+use aoc2025::generate::{self, Rng};
+
+const USAGE: &str = "Usage: aoc gen <day> [--seed=N] [--param key=value]...";
+
+pub fn main() {
+    let args = Args::from_env();
+    match args.positional() {
+        [command, day] if command == "gen" => {
+            args.expect_options(&["seed"]);
+            let day: u32 = day.parse().expect(USAGE);
+            let seed: u64 = args.option("seed").map_or(1, |s| s.parse().expect(USAGE));
+            let mut rng = Rng::new(seed);
+            let lines = match generate::for_day(day, &mut rng, &args) {
+                Ok(lines) => lines,
+                Err(e) => {
+                    eprintln!("Can't generate day {day}: {e}");
+                    std::process::exit(1);
+                }
+            };
+
+            let mut out = BufWriter::new(stdout().lock());
+            for line in lines {
+                writeln!(out, "{line}").unwrap();
+            }
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            std::process::exit(2);
+        }
+    }
+}
@@ -1,30 +1,263 @@
-use aoc2025::common::read_input;
+use std::{
+    f64::consts::PI,
+    fs,
+    io::{BufRead, StdoutLock, Write, stdin, stdout},
+    thread,
+    time::Duration,
+};
+
+use aoc2025::common::{Args, ParseError, read_input};
 use regex::Regex;
 
 const DAY: u32 = 1;
 
+// Puzzle parameters, overridable with `--param key=value`
+#[derive(Clone, Debug)]
+pub struct Params {
+    start: i64,
+    size: i64,
+    targets: Vec<i64>,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            start: 50,
+            size: 100,
+            targets: vec![0],
+        }
+    }
+}
+
+impl Params {
+    fn from_args(args: &Args) -> Result<Params, String> {
+        args.expect_params(&["start", "size", "targets"]);
+        let default = Params::default();
+        // Targets are given as a comma separated list, e.g. `targets=0,50`
+        let targets = match args.param("targets", String::new()).as_str() {
+            "" => default.targets,
+            list => list
+                .split(',')
+                .map(|t| t.parse().map_err(|e| format!("Invalid target {t}: {e}")))
+                .collect::<Result<_, _>>()?,
+        };
+        Params {
+            start: args.param("start", default.start),
+            size: args.param("size", default.size),
+            targets,
+        }
+        .checked()
+    }
+
+    // The parameters, if the dial has any positions
+    fn checked(self) -> Result<Params, String> {
+        if self.size <= 0 {
+            return Err(format!(
+                "The dial needs at least one position, got size {}",
+                self.size
+            ));
+        }
+        Ok(self)
+    }
+}
+
+// What happened during a single rotation
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub rotation: i64,
+    pub start: i64,
+    pub end: i64,
+    pub full_cycles: i64,
+    // Clicks that left the dial pointing at a target, including the last one
+    pub crossings: i64,
+    pub landed: bool,
+}
+
+// Dial with positions 0..size, counting how often it points at a target
+#[derive(Clone, Debug)]
+pub struct Dial {
+    size: i64,
+    position: i64,
+    targets: Vec<i64>,
+    landings: i64,
+    // Kept in i128, as every rotation can cross up to i64::MAX times
+    crossings: i128,
+}
+
+impl Dial {
+    pub fn new(size: i64, start: i64, targets: &[i64]) -> Dial {
+        assert!(size > 0, "The dial needs at least one position");
+        let mut targets = targets
+            .iter()
+            .map(|t| t.rem_euclid(size))
+            .collect::<Vec<_>>();
+        targets.sort();
+        targets.dedup();
+        Dial {
+            size,
+            position: start.rem_euclid(size),
+            targets,
+            landings: 0,
+            crossings: 0,
+        }
+    }
+
+    pub fn from_params(params: &Params) -> Dial {
+        Dial::new(params.size, params.start, &params.targets)
+    }
+
+    pub fn position(&self) -> i64 {
+        self.position
+    }
+
+    pub fn size(&self) -> i64 {
+        self.size
+    }
+
+    pub fn targets(&self) -> &[i64] {
+        &self.targets
+    }
+
+    // Rotations that ended on a target
+    pub fn landings(&self) -> i64 {
+        self.landings
+    }
+
+    // Clicks that ended on a target
+    pub fn crossings(&self) -> i128 {
+        self.crossings
+    }
+
+    // Turn the dial, right for positive rotations and left for negative ones
+    pub fn rotate(&mut self, rotation: i64) -> Step {
+        let start = self.position;
+        // Without wrapping, the clicks visit (start, start + rotation] going
+        // right and [start + rotation, start) going left. Counting the
+        // multiples of the size in between (shifted by the target) gives the
+        // crossings. Done in i128, as the rotation can be anything. A single
+        // rotation never has more crossings than clicks, so the sum fits in
+        // an i64.
+        let (low, high) = if rotation >= 0 {
+            (start as i128, start as i128 + rotation as i128)
+        } else {
+            (start as i128 + rotation as i128 - 1, start as i128 - 1)
+        };
+        let size = self.size as i128;
+        let crossings = self
+            .targets
+            .iter()
+            .map(|&t| (high - t as i128).div_euclid(size) - (low - t as i128).div_euclid(size))
+            .sum::<i128>() as i64;
+        self.position = (start as i128 + rotation as i128).rem_euclid(size) as i64;
+        let landed = self.targets.contains(&self.position);
+        self.crossings += crossings as i128;
+        self.landings += landed as i64;
+        Step {
+            rotation,
+            start,
+            end: self.position,
+            full_cycles: (rotation / self.size).abs(),
+            crossings,
+            landed,
+        }
+    }
+}
+
 pub fn main() {
+    let args = Args::from_env();
+    args.expect_options(&["trace", "csv", "animate", "speed", "stdin"]);
+    let params = Params::from_args(&args).unwrap_or_else(|e| panic!("Invalid parameters: {e}"));
+    if args.flag("stdin") {
+        // Rotations from standard input, which may not fit in memory
+        stdin_options(&args).unwrap_or_else(|e| panic!("{e}"));
+        let (part1_result, part2_result) =
+            stream(stdin().lock(), &params).unwrap_or_else(|e| panic!("Invalid input: {e}"));
+        println!("Day {DAY}, part 1: {part1_result}");
+        println!("Day {DAY}, part 2: {part2_result}");
+        return;
+    }
+    let delay = args
+        .value("speed")
+        .map_or(Ok(Duration::from_millis(20)), click_delay)
+        .unwrap_or_else(|e| panic!("{e}"));
+    let csv = args.value("csv");
     let input = day_input();
-    let part1_result = part1(&input);
+    if args.flag("trace") || csv.is_some() {
+        let steps = trace(&input, &params);
+        if args.flag("trace") {
+            print!("{}", trace_table(&steps));
+        }
+        if let Some(path) = csv {
+            fs::write(path, trace_csv(&steps))
+                .unwrap_or_else(|e| panic!("Could not write {path}: {e}"));
+        }
+    }
+    if args.flag("animate") {
+        animate(&input, &params, delay);
+    }
+    let part1_result = part1(&input, &params);
     println!("Day {DAY}, part 1: {part1_result}");
-    let part2_result = part2(&input);
+    let part2_result = part2(&input, &params);
     println!("Day {DAY}, part 2: {part2_result}");
 }
 
+// Streamed rotations aren't kept, so there's nothing to trace or animate
+fn stdin_options(args: &Args) -> Result<(), String> {
+    match ["trace", "csv", "animate", "speed"]
+        .into_iter()
+        .find(|option| args.flag(option))
+    {
+        Some(option) => Err(format!("--{option} can't be used with --stdin")),
+        None => Ok(()),
+    }
+}
+
+// Parse a single rotation like L68, on the line at the given index
+fn parse_rotation(pattern: &Regex, i: usize, s: &str) -> Result<i64, ParseError> {
+    let caps = pattern
+        .captures(s)
+        .ok_or_else(|| ParseError::at(i, format!("Expected a rotation like L68, got {s}")))?;
+    let dir = &caps[1];
+    let dist = caps[2]
+        .parse::<i64>()
+        .map_err(|e| ParseError::at(i, format!("Invalid distance: {e}")))?;
+    Ok(if dir == "L" { -dist } else { dist })
+}
+
+fn rotation_pattern() -> Regex {
+    Regex::new(r"^([LR])(\d+)$").unwrap()
+}
+
 // Parse input
-fn process_input(input: Vec<String>) -> Vec<i64> {
-    let re = Regex::new(r"^([LR])(\d+)$").unwrap();
+pub fn process_input(input: Vec<String>) -> Result<Vec<i64>, ParseError> {
+    let pattern = rotation_pattern();
     input
         .iter()
-        .map(|s| {
-            let caps = re.captures(s).unwrap();
-            let dir = &caps[1];
-            let dist = &caps[2].parse::<i64>().unwrap();
-            if dir == "L" { -dist } else { *dist }
-        })
+        .enumerate()
+        .map(|(i, s)| parse_rotation(&pattern, i, s))
         .collect()
 }
 
+// Solve both parts in a single pass, reading one rotation at a time, so the
+// input can be as long as needed
+pub fn stream(mut reader: impl BufRead, params: &Params) -> Result<(i64, i128), ParseError> {
+    let pattern = rotation_pattern();
+    let mut dial = Dial::from_params(params);
+    let mut line = String::new();
+    for i in 0.. {
+        line.clear();
+        let read = reader
+            .read_line(&mut line)
+            .map_err(|e| ParseError::at(i, format!("Could not read input: {e}")))?;
+        if read == 0 {
+            break;
+        }
+        let rotation = parse_rotation(&pattern, i, line.trim_end_matches(['\n', '\r']))?;
+        dial.rotate(rotation);
+    }
+    Ok((dial.landings(), dial.crossings()))
+}
+
 // Parse sample input
 fn _sample_input() -> Vec<i64> {
     let data = "L68
@@ -40,98 +273,348 @@ L82"
     .split('\n')
     .map(|s| s.to_string())
     .collect();
-    process_input(data)
+    process_input(data).unwrap()
 }
 
 // Parse day's input
 fn day_input() -> Vec<i64> {
-    process_input(read_input(DAY))
+    process_input(read_input(DAY)).unwrap_or_else(|e| panic!("Invalid input: {e}"))
 }
 
-fn part1(input: &Vec<i64>) -> i64 {
-    let mut pos = 50;
-    let mut zeros = 0;
-    for change in input {
-        pos += *change % 100 + 100;
-        pos = pos % 100;
-        if pos == 0 {
-            zeros += 1;
-        }
-        if pos < 0 || pos >= 100 {
-            println!("Position out of bounds: {pos}");
-        }
+pub fn part1(input: &[i64], params: &Params) -> i64 {
+    let mut dial = Dial::from_params(params);
+    input.iter().for_each(|rotation| {
+        dial.rotate(*rotation);
+    });
+    dial.landings()
+}
+
+pub fn part2(input: &[i64], params: &Params) -> i128 {
+    let mut dial = Dial::from_params(params);
+    input.iter().for_each(|rotation| {
+        dial.rotate(*rotation);
+    });
+    dial.crossings()
+}
+
+// Every rotation, with where it started and ended
+pub fn trace(input: &[i64], params: &Params) -> Vec<Step> {
+    let mut dial = Dial::from_params(params);
+    input
+        .iter()
+        .map(|rotation| dial.rotate(*rotation))
+        .collect()
+}
+
+// Rotation the way the input writes it
+fn label(rotation: i64) -> String {
+    let dir = if rotation < 0 { 'L' } else { 'R' };
+    format!("{dir}{}", rotation.unsigned_abs())
+}
+
+// Trace as an aligned table, one rotation per row
+pub fn trace_table(steps: &[Step]) -> String {
+    let mut table = format!(
+        "{:>10} {:>6} {:>6} {:>12} {:>10}\n",
+        "rotation", "start", "end", "full cycles", "crossings"
+    );
+    for step in steps {
+        table += &format!(
+            "{:>10} {:>6} {:>6} {:>12} {:>10}{}\n",
+            label(step.rotation),
+            step.start,
+            step.end,
+            step.full_cycles,
+            step.crossings,
+            if step.landed { "  landed" } else { "" }
+        );
     }
-    zeros
-}
-
-fn part2(input: &Vec<i64>) -> i64 {
-    let mut pos = 50;
-    let mut zeros = 0;
-    for change in input {
-        let start = pos;
-        pos += *change % 100 + 100;
-        pos = pos % 100;
-
-        // Calculate when we pass zero
-        let full_cycles = (*change / 100).abs();
-        zeros += full_cycles;
-        if start == 0 {
-            // Already on zero
-            continue;
-        } else if pos == 0 {
-            // Turn ends at zero
-            if start != pos {
-                //println!("Landing on zero from {start}");
-                zeros += 1;
-            }
-        } else if *change > 0 {
-            // Clockwise
-            if start > pos {
-                //println!("Passed zero between {start} and {pos}");
-                zeros += 1;
-            }
-        } else {
-            // Counter-clockwise
-            if start < pos {
-                //println!("Passed zero between {start} and {pos} CC");
-                zeros += 1;
-            }
-        }
+    table
+}
 
-        //println!("Change: {change}, Position: {pos}, Full cycles: {full_cycles}, Zeros: {zeros}");
-        if pos < 0 || pos >= 100 {
-            println!("Position out of bounds: {pos}");
+// Trace as CSV, for diffing against other solvers
+pub fn trace_csv(steps: &[Step]) -> String {
+    let mut csv = "rotation,start,end,full_cycles,crossings,landed\n".to_string();
+    for step in steps {
+        csv += &format!(
+            "{},{},{},{},{},{}\n",
+            label(step.rotation),
+            step.start,
+            step.end,
+            step.full_cycles,
+            step.crossings,
+            step.landed
+        );
+    }
+    csv
+}
+
+// Dial as a ring of ticks, with the targets as `o` and the pointer as `@`
+pub fn draw_dial(dial: &Dial, radius: usize) -> Vec<String> {
+    // Characters are about twice as tall as they are wide
+    let (width, height) = (4 * radius + 1, 2 * radius + 1);
+    let mut grid = vec![vec![' '; width]; height];
+    // Zero at the top, turning clockwise
+    let mut mark = |turn: f64, c: char| {
+        let angle = 2.0 * PI * turn;
+        let x = (2.0 * radius as f64 * (1.0 + angle.sin())).round() as usize;
+        let y = (radius as f64 * (1.0 - angle.cos())).round() as usize;
+        grid[y][x] = c;
+    };
+    let size = dial.size() as f64;
+    // Enough ticks to draw a closed ring, without a tick per position on huge dials
+    let ticks = dial.size().min(8 * radius as i64);
+    (0..ticks).for_each(|i| mark(i as f64 / ticks as f64, '.'));
+    dial.targets()
+        .iter()
+        .for_each(|&t| mark(t as f64 / size, 'o'));
+    mark(dial.position() as f64 / size, '@');
+    let center = dial.position().to_string();
+    let start = (2 * radius).saturating_sub(center.len() / 2);
+    for (x, c) in (start..width).zip(center.chars()) {
+        grid[radius][x] = c;
+    }
+    grid.iter().map(|row| row.iter().collect()).collect()
+}
+
+// Time between animation frames for a speed given in clicks per second
+fn click_delay(speed: &str) -> Result<Duration, String> {
+    let clicks: f64 = speed
+        .parse()
+        .map_err(|e| format!("Invalid speed {speed}: {e}"))?;
+    if clicks > 0.0 {
+        Duration::try_from_secs_f64(1.0 / clicks)
+            .map_err(|_| format!("Speed {speed} is too slow to animate"))
+    } else {
+        Err(format!("Speed must be positive, got {speed}"))
+    }
+}
+
+// Turn the dial in the terminal, one click at a time
+fn animate(input: &[i64], params: &Params, delay: Duration) {
+    let mut dial = Dial::from_params(params);
+    let mut out = stdout().lock();
+    let frame = |out: &mut StdoutLock, dial: &Dial, status: &str| {
+        // Move the cursor back to the top left and redraw
+        write!(out, "\x1b[H").unwrap();
+        for line in draw_dial(dial, 10) {
+            writeln!(out, "{line}").unwrap();
+        }
+        writeln!(out, "{status:<60}").unwrap();
+        out.flush().unwrap();
+        thread::sleep(delay);
+    };
+    write!(out, "\x1b[2J").unwrap();
+    for (i, &rotation) in input.iter().enumerate() {
+        // Full turns don't move the pointer, so only the rest is animated
+        let rest = rotation % dial.size();
+        dial.rotate(rotation - rest);
+        for _ in 0..rest.abs() {
+            dial.rotate(rest.signum());
+            let status = format!(
+                "{}/{}: {}  crossings {}",
+                i + 1,
+                input.len(),
+                label(rotation),
+                dial.crossings()
+            );
+            frame(&mut out, &dial, &status);
         }
     }
-    zeros
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc2025::{
+        check,
+        generate::{self, Day01Size},
+        reference,
+    };
 
     #[test]
     fn test_part1() {
         let input = _sample_input();
-        assert_eq!(3, part1(&input));
+        assert_eq!(3, part1(&input, &Params::default()));
     }
 
     #[test]
     fn test_part2() {
         let input = _sample_input();
-        assert_eq!(6, part2(&input));
+        assert_eq!(6, part2(&input, &Params::default()));
+    }
+
+    #[test]
+    fn test_trace() {
+        let steps = trace(&_sample_input(), &Params::default());
+        assert_eq!(
+            Step {
+                rotation: -68,
+                start: 50,
+                end: 82,
+                full_cycles: 0,
+                crossings: 1,
+                landed: false,
+            },
+            steps[0]
+        );
+        assert_eq!(6, steps.iter().map(|s| s.crossings).sum::<i64>());
+
+        let step = Dial::new(100, 50, &[0]).rotate(1000);
+        assert_eq!((50, 10, 10), (step.end, step.full_cycles, step.crossings));
+    }
+
+    #[test]
+    fn test_trace_output() {
+        let steps = trace(&_sample_input(), &Params::default());
+        let csv = trace_csv(&steps);
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(11, lines.len());
+        assert_eq!("L68,50,82,0,1,false", lines[1]);
+        assert_eq!("L55,55,0,0,1,true", lines[6]);
+        let table = trace_table(&steps);
+        assert!(table.lines().nth(6).unwrap().ends_with("landed"));
+
+        let dial = Dial::new(4, 1, &[0]);
+        assert_eq!(vec!["  o  ", ". 1 @", "  .  "], draw_dial(&dial, 1));
+    }
+
+    #[test]
+    fn test_click_delay() {
+        assert_eq!(Ok(Duration::from_millis(20)), click_delay("50"));
+        assert_eq!(Ok(Duration::from_secs(2)), click_delay("0.5"));
+        assert_eq!(
+            Err("Speed must be positive, got 0".to_string()),
+            click_delay("0")
+        );
+        assert!(click_delay("-10").is_err());
+        assert!(click_delay("NaN").is_err());
+        assert!(click_delay("1e-320").is_err());
+        assert!(click_delay("fast").is_err());
+    }
+
+    #[test]
+    fn test_stdin_options() {
+        let args = |list: &[&str]| Args::parse(list.iter().map(|s| s.to_string()));
+        assert_eq!(Ok(()), stdin_options(&args(&["--stdin"])));
+        assert_eq!(
+            Ok(()),
+            stdin_options(&args(&["--stdin", "--param", "size=10"]))
+        );
+        assert_eq!(
+            Err("--trace can't be used with --stdin".to_string()),
+            stdin_options(&args(&["--stdin", "--trace"]))
+        );
+        assert!(stdin_options(&args(&["--stdin", "--csv=trace.csv"])).is_err());
+        assert!(stdin_options(&args(&["--animate", "--stdin"])).is_err());
+        assert!(stdin_options(&args(&["--stdin", "--speed=10"])).is_err());
+    }
+
+    #[test]
+    fn test_stream() {
+        let data = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n";
+        assert_eq!(Ok((3, 6)), stream(data.as_bytes(), &Params::default()));
+        // Windows line endings, and no newline at the end
+        assert_eq!(
+            Ok((1, 1)),
+            stream("L20\r\nR70".as_bytes(), &Params::default())
+        );
+        let error = stream("L20\nR\nL5\n".as_bytes(), &Params::default()).unwrap_err();
+        assert_eq!(2, error.line);
+    }
+
+    #[test]
+    fn test_large_rotations() {
+        // Crossings that used to overflow an i64 over 101 rotations
+        let input = vec![i64::MAX; 101];
+        // Every multiple of 100 passed on the way from 50 to 50 + 101 * i64::MAX
+        let expected = (50 + 101 * i64::MAX as i128) / 100;
+        assert_eq!(expected, part2(&input, &Params::default()));
+        let data = "R9223372036854775807\n".repeat(101);
+        assert_eq!(
+            Ok((1, expected)),
+            stream(data.as_bytes(), &Params::default())
+        );
+    }
+
+    #[test]
+    fn test_params() {
+        let args = |list: &[&str]| Args::parse(list.iter().map(|s| s.to_string()));
+        let params = Params::from_args(&args(&["--param", "targets=0,50"])).unwrap();
+        assert_eq!(vec![0, 50], params.targets);
+        assert_eq!(
+            Err("The dial needs at least one position, got size 0".to_string()),
+            Params::from_args(&args(&["--param", "size=0"])).map(|p| p.size)
+        );
+        assert!(Params::from_args(&args(&["--param", "size=-5"])).is_err());
+        assert!(Params::from_args(&args(&["--param", "targets=0,x"])).is_err());
+    }
+
+    #[test]
+    fn test_targets() {
+        // Each target counts like zero would on a dial turned by the target
+        let input = _sample_input();
+        let params = Params {
+            targets: vec![0, 50, 150],
+            ..Params::default()
+        };
+        let shifted = |start: i64| Params {
+            start,
+            ..Params::default()
+        };
+        assert_eq!(
+            part1(&input, &shifted(50)) + part1(&input, &shifted(0)),
+            part1(&input, &params)
+        );
+        assert_eq!(
+            part2(&input, &shifted(50)) + part2(&input, &shifted(0)),
+            part2(&input, &params)
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        let lines = |data: &str| data.split('\n').map(|s| s.to_string()).collect();
+        assert!(process_input(lines("L")).is_err());
+        assert!(process_input(lines("R10\nX10")).is_err());
+        assert!(process_input(lines("L99999999999999999999")).is_err());
+    }
+
+    #[test]
+    fn differential() {
+        let size = Day01Size {
+            rotations: 30,
+            max_distance: 250,
+        };
+        let params = Params {
+            size: 37,
+            start: 5,
+            ..Params::default()
+        };
+        check::differential(
+            300,
+            |rng| generate::day01(rng, &size).unwrap(),
+            process_input,
+            |input| (part1(input, &params), part2(input, &params)),
+            |input| {
+                (
+                    reference::day01::part1(input, params.start, params.size),
+                    reference::day01::part2(input, params.start, params.size) as i128,
+                )
+            },
+        );
     }
 
     #[test]
     fn real_part1() {
         let input = day_input();
-        assert_eq!(989, part1(&input));
+        assert_eq!(989, part1(&input, &Params::default()));
     }
 
     #[test]
     fn real_part2() {
         let input = day_input();
-        assert_eq!(5941, part2(&input));
+        assert_eq!(5941, part2(&input, &Params::default()));
     }
 }
 
@@ -1,29 +1,128 @@
-use aoc2025::common::read_input;
+use std::{collections::HashSet, str::FromStr};
+
+use aoc2025::common::{Args, ParseError, read_input};
 use itertools::Itertools;
 use regex::Regex;
+use serde::Serialize;
 
 const DAY: u32 = 2;
 
+// How many times a block may be repeated to make an invalid ID
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Repeats {
+    // One of the listed counts, e.g. `3` or `2,4`
+    AnyOf(Vec<u32>),
+    // The count or more, e.g. `2+`
+    AtLeast(u32),
+    // Any prime count, `primes`
+    Primes,
+}
+
+impl Repeats {
+    pub fn allows(&self, times: u32) -> bool {
+        match self {
+            Repeats::AnyOf(counts) => counts.contains(&times),
+            Repeats::AtLeast(min) => times >= *min,
+            Repeats::Primes => times >= 2 && (2..times).all(|d| !times.is_multiple_of(d)),
+        }
+    }
+}
+
+impl FromStr for Repeats {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Repeats, String> {
+        let count = |c: &str| match c.parse::<u32>() {
+            Ok(n) if n >= 2 => Ok(n),
+            _ => Err(format!("Expected a repeat count of at least 2, got {c}")),
+        };
+        if s == "primes" {
+            Ok(Repeats::Primes)
+        } else if let Some(min) = s.strip_suffix('+') {
+            Ok(Repeats::AtLeast(count(min)?))
+        } else {
+            Ok(Repeats::AnyOf(
+                s.split(',').map(count).collect::<Result<_, _>>()?,
+            ))
+        }
+    }
+}
+
+// Puzzle parameters, overridable with `--param key=value`
+#[derive(Clone, Debug)]
+pub struct Params {
+    radix: u32,
+    repeats1: Repeats,
+    repeats2: Repeats,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            radix: 10,
+            repeats1: Repeats::AnyOf(vec![2]),
+            repeats2: Repeats::AtLeast(2),
+        }
+    }
+}
+
+impl Params {
+    fn from_args(args: &Args) -> Params {
+        args.expect_params(&["radix", "repeats1", "repeats2"]);
+        let default = Params::default();
+        let radix = args.param("radix", default.radix);
+        if !(2..=36).contains(&radix) {
+            panic!("Radix must be between 2 and 36, got {radix}");
+        }
+        Params {
+            radix,
+            repeats1: args.param("repeats1", default.repeats1),
+            repeats2: args.param("repeats2", default.repeats2),
+        }
+    }
+}
+
 pub fn main() {
+    let args = Args::from_env();
+    args.expect_options(&["explain"]);
+    let params = Params::from_args(&args);
     let input = day_input();
-    let part1_result = part1(&input);
+    if args.flag("explain") {
+        let reports = explain(&input, &params);
+        match args.option("explain") {
+            Some("json") => println!("{}", serde_json::to_string_pretty(&reports).unwrap()),
+            Some(format) => panic!("Unknown format {format}, expected json"),
+            None => print!("{}", explanation_text(&reports)),
+        }
+    }
+    let part1_result = part1(&input, &params);
     println!("Day {DAY}, part 1: {part1_result}");
-    let part2_result = part2(&input);
+    let part2_result = part2(&input, &params);
     println!("Day {DAY}, part 2: {part2_result}");
 }
 
 // Parse input
-fn process_input(input: Vec<String>) -> Vec<(i64, i64)> {
-    let pattern = Regex::new(r"(\d+)-(\d+)").unwrap();
-    input
+pub fn process_input(input: Vec<String>) -> Result<Vec<(i64, i64)>, ParseError> {
+    let pattern = Regex::new(r"^(\d+)-(\d+)$").unwrap();
+    let line = input
         .first()
-        .unwrap()
-        .split(",")
+        .ok_or_else(|| ParseError::at(0, "Expected a line of ID ranges"))?;
+    if let Some(extra) = input.iter().skip(1).position(|l| !l.is_empty()) {
+        return Err(ParseError::at(
+            extra + 1,
+            "Expected a single line of ID ranges",
+        ));
+    }
+    line.split(",")
         .map(|rule| {
-            let captures = pattern.captures(rule).unwrap();
-            let start = &captures[1].parse::<i64>().unwrap();
-            let end = &captures[2].parse::<i64>().unwrap();
-            (*start, *end)
+            let captures = pattern.captures(rule).ok_or_else(|| {
+                ParseError::at(0, format!("Expected a range like 11-22, got {rule}"))
+            })?;
+            let parse = |s: &str| {
+                s.parse::<i64>()
+                    .map_err(|e| ParseError::at(0, format!("Invalid ID {s}: {e}")))
+            };
+            Ok((parse(&captures[1])?, parse(&captures[2])?))
         })
         .collect()
 }
@@ -34,99 +133,409 @@ fn _sample_input() -> Vec<(i64, i64)> {
         .split('\n')
         .map(|s| s.to_string())
         .collect();
-    process_input(data)
+    process_input(data).unwrap()
 }
 
 // Parse day's input
 fn day_input() -> Vec<(i64, i64)> {
-    process_input(read_input(DAY))
-}
-
-// Returns numbers that have exactly two repeating halves
-fn check_2_repeats(start: i64, end: i64) -> Vec<i64> {
-    (start..=end)
-        .filter(|i| {
-            let s = i.to_string();
-            if s.len() % 2 == 0 {
-                let (left, right) = s.split_at(s.len() / 2);
-                if left == right {
-                    return true;
-                }
-            }
-            false
+    process_input(read_input(DAY)).unwrap_or_else(|e| panic!("Invalid input: {e}"))
+}
+
+// Digits of a (non-negative) ID in the given radix
+fn digits(id: i64, radix: u32) -> String {
+    let mut digits = Vec::new();
+    let mut rest = id as u64;
+    loop {
+        digits.push(char::from_digit((rest % radix as u64) as u32, radix).unwrap());
+        rest /= radix as u64;
+        if rest == 0 {
+            break;
+        }
+    }
+    digits.iter().rev().collect()
+}
+
+// Block of digits that is repeated to make an invalid ID
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Pattern {
+    pub block: String,
+    pub times: u32,
+}
+
+// Why the ID is invalid, if it is. With several allowed repeat counts, the
+// shortest block is reported.
+pub fn pattern(id: i64, radix: u32, repeats: &Repeats) -> Option<Pattern> {
+    let digits = digits(id, radix);
+    let len = digits.len() as u32;
+    (2..=len).rev().find_map(|times| {
+        let block = &digits[..(len / times) as usize];
+        (len.is_multiple_of(times)
+            && repeats.allows(times)
+            && digits == block.repeat(times as usize))
+        .then(|| Pattern {
+            block: block.to_string(),
+            times,
         })
-        .collect()
+    })
 }
 
-// Returns numbers that consist of pattern repeating 2+ times
-fn check_n_patterns(start: i64, end: i64) -> Vec<i64> {
-    //println!("Generating IDs for range {start}-{end}");
-    let start_str = start.to_string();
-    let end_str = end.to_string();
-    let max_pattern = (end_str.len() / 2).try_into().unwrap();
-    (1..10u64.pow(max_pattern))
-        .filter_map(|p| {
-            let pattern = p.to_string();
-            let repeats = end_str.len() / pattern.len();
-            let res = pattern.repeat(repeats);
-            let val = res.parse::<i64>().unwrap();
-            if val >= start && val <= end && repeats > 1 {
-                //println!("1: {val} (pattern {pattern})");
-                return Some(val);
-            }
+// Sum of the IDs in low..=high, all `len` digits long, that are a block of
+// `period` digits repeated. Those are the block times 1 0..01 0..01, so
+// it's the sum of the blocks that land in the range, times that multiplier.
+fn periodic_sum(low: i128, high: i128, radix: i128, len: u32, period: u32) -> i128 {
+    let multiplier = (radix.pow(len) - 1) / (radix.pow(period) - 1);
+    let first = ((low + multiplier - 1) / multiplier).max(radix.pow(period - 1));
+    let last = (high / multiplier).min(radix.pow(period) - 1);
+    if first > last {
+        return 0;
+    }
+    multiplier * (first + last) * (last - first + 1) / 2
+}
 
-            let repeats2 = start_str.len() / pattern.len();
-            let res2 = pattern.repeat(repeats2);
-            let val2 = res2.parse::<i64>().unwrap();
-            if val2 >= start && val2 <= end && repeats2 > 1 {
-                //println!("2: {val2} (pattern {pattern})");
-                return Some(val2);
+// Sum of the IDs in start..=end made of a block repeated a number of times
+// accepted by `repeats`
+fn invalid_sum(start: i64, end: i64, radix: u32, repeats: &Repeats) -> i128 {
+    // Longest ID that fits in an i64
+    let max_len = digits(i64::MAX, radix).len() as u32;
+    let radix = radix as i128;
+    (2..=max_len)
+        .map(|len| {
+            let low = (start as i128).max(radix.pow(len - 1));
+            let high = (end as i128).min(radix.pow(len) - 1);
+            if low > high {
+                return 0;
+            }
+            // An ID whose shortest block has `period` digits is also periodic
+            // with every multiple of it, so subtract those found for the
+            // divisors to get the IDs with exactly this shortest block
+            let periods = (1..len)
+                .filter(|p| len.is_multiple_of(*p))
+                .collect::<Vec<_>>();
+            let mut shortest: Vec<(u32, i128)> = Vec::new();
+            for &period in &periods {
+                let longer = shortest
+                    .iter()
+                    .filter(|(p, _)| period.is_multiple_of(*p))
+                    .map(|(_, sum)| sum)
+                    .sum::<i128>();
+                let sum = periodic_sum(low, high, radix, len, period) - longer;
+                shortest.push((period, sum));
             }
-            None
+            // The shortest block can be repeated as a longer block, so the ID
+            // counts if some allowed repeat count has a block length it divides
+            shortest
+                .iter()
+                .filter(|(period, _)| {
+                    (2..=len).any(|times| {
+                        len.is_multiple_of(times)
+                            && repeats.allows(times)
+                            && (len / times).is_multiple_of(*period)
+                    })
+                })
+                .map(|(_, sum)| sum)
+                .sum::<i128>()
         })
-        .collect()
+        .sum()
+}
+
+// Overlapping ranges combined, so no ID is counted twice
+fn merge_ranges(input: &[(i64, i64)]) -> Vec<(i64, i64)> {
+    let mut merged: Vec<(i64, i64)> = Vec::new();
+    for &(start, end) in input.iter().filter(|(s, e)| s <= e).sorted() {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
 }
 
-fn part1(input: &Vec<(i64, i64)>) -> i64 {
+// IDs with exactly two repeating halves (by default)
+pub fn part1(input: &[(i64, i64)], params: &Params) -> i128 {
     input
         .iter()
-        .map(|r| check_2_repeats(r.0, r.1).iter().sum::<i64>())
+        .map(|&(start, end)| invalid_sum(start, end, params.radix, &params.repeats1))
         .sum()
 }
 
-fn part2(input: &Vec<(i64, i64)>) -> i64 {
-    input
+// IDs that consist of a pattern repeating 2+ times (by default), each counted once
+pub fn part2(input: &[(i64, i64)], params: &Params) -> i128 {
+    merge_ranges(input)
         .iter()
-        .flat_map(|r| check_n_patterns(r.0, r.1))
-        .unique()
+        .map(|&(start, end)| invalid_sum(start, end, params.radix, &params.repeats2))
         .sum()
 }
 
+// Invalid ID found while explaining a range
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct InvalidId {
+    pub id: i64,
+    pub pattern: Pattern,
+    // Already found in an earlier range, so part 2 doesn't count it again
+    pub duplicate: bool,
+}
+
+// Invalid IDs of a single input range, by the part 2 rules
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RangeReport {
+    pub start: i64,
+    pub end: i64,
+    pub invalid: Vec<InvalidId>,
+    // Sum of the IDs not counted in an earlier range
+    pub subtotal: i128,
+}
+
+// Invalid IDs in start..=end, built from the blocks rather than checking every
+// ID, but still one by one, so only for ranges with a sensible number of them
+fn invalid_ids(start: i64, end: i64, radix: u32, repeats: &Repeats) -> Vec<i64> {
+    let max_len = digits(i64::MAX, radix).len() as u32;
+    let radix = radix as i128;
+    let mut ids = Vec::new();
+    for len in 2..=max_len {
+        for times in (2..=len).filter(|t| len.is_multiple_of(*t) && repeats.allows(*t)) {
+            let period = len / times;
+            let multiplier = (radix.pow(len) - 1) / (radix.pow(period) - 1);
+            let first = radix
+                .pow(period - 1)
+                .max((start as i128 + multiplier - 1) / multiplier);
+            let last = (radix.pow(period) - 1).min(end as i128 / multiplier);
+            ids.extend((first..=last).map(|block| (block * multiplier) as i64));
+        }
+    }
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+// Which IDs each range contributes to part 2, and why
+pub fn explain(input: &[(i64, i64)], params: &Params) -> Vec<RangeReport> {
+    let mut seen = HashSet::new();
+    input
+        .iter()
+        .map(|&(start, end)| {
+            let invalid = invalid_ids(start, end, params.radix, &params.repeats2)
+                .into_iter()
+                .map(|id| InvalidId {
+                    id,
+                    pattern: pattern(id, params.radix, &params.repeats2).unwrap(),
+                    duplicate: !seen.insert(id),
+                })
+                .collect::<Vec<_>>();
+            let subtotal = invalid
+                .iter()
+                .filter(|i| !i.duplicate)
+                .map(|i| i.id as i128)
+                .sum();
+            RangeReport {
+                start,
+                end,
+                invalid,
+                subtotal,
+            }
+        })
+        .collect()
+}
+
+pub fn explanation_text(reports: &[RangeReport]) -> String {
+    let mut text = String::new();
+    for report in reports {
+        text += &format!("{}-{}\n", report.start, report.end);
+        for invalid in &report.invalid {
+            text += &format!(
+                "  {} = {} x {}{}\n",
+                invalid.id,
+                invalid.pattern.block,
+                invalid.pattern.times,
+                if invalid.duplicate {
+                    " (already counted)"
+                } else {
+                    ""
+                }
+            );
+        }
+        text += &format!("  subtotal {}\n", report.subtotal);
+    }
+    text
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc2025::{
+        check,
+        generate::{self, Day02Size},
+        reference,
+    };
 
     #[test]
     fn test_part1() {
         let input = _sample_input();
-        assert_eq!(1227775554, part1(&input));
+        assert_eq!(1227775554, part1(&input, &Params::default()));
     }
 
     #[test]
     fn test_part2() {
         let input = _sample_input();
-        assert_eq!(4174379265, part2(&input));
+        assert_eq!(4174379265, part2(&input, &Params::default()));
+    }
+
+    #[test]
+    fn test_large_ranges() {
+        // Overlapping ranges count the IDs in both for part 1, but once for part 2
+        let input = vec![(95, 1200), (1000, 2000), (5, 5)];
+        assert_eq!(
+            reference::day02::part1(&input) as i128,
+            part1(&input, &Params::default())
+        );
+        assert_eq!(
+            reference::day02::part2(&input) as i128,
+            part2(&input, &Params::default())
+        );
+
+        // 9 + 90 + 900 + ... halves, each times 10^k + 1
+        let input = vec![(1, 999_999_999_999_999_999)];
+        let expected = (1..=9)
+            .map(|k| {
+                let (first, last) = (10i128.pow(k - 1), 10i128.pow(k) - 1);
+                (10i128.pow(k) + 1) * (first + last) * (last - first + 1) / 2
+            })
+            .sum::<i128>();
+        assert_eq!(expected, part1(&input, &Params::default()));
+        assert!(part2(&input, &Params::default()) > part1(&input, &Params::default()));
+    }
+
+    #[test]
+    fn test_radix_and_repeats() {
+        let three_times = Repeats::AnyOf(vec![3]);
+        let pattern_of =
+            |id, radix, repeats: &Repeats| pattern(id, radix, repeats).map(|p| (p.block, p.times));
+        assert_eq!(
+            Some(("12".to_string(), 3)),
+            pattern_of(121212, 10, &three_times)
+        );
+        assert_eq!(None, pattern_of(1212, 10, &three_times));
+        assert_eq!(
+            Some(("1".to_string(), 4)),
+            pattern_of(1111, 10, &Repeats::AtLeast(2))
+        );
+        assert_eq!(
+            Some(("11".to_string(), 2)),
+            pattern_of(1111, 10, &Repeats::AnyOf(vec![2]))
+        );
+        // 0b101101
+        assert_eq!(
+            Some(("101".to_string(), 2)),
+            pattern_of(45, 2, &Repeats::AtLeast(2))
+        );
+        assert_eq!(
+            Some(("z".to_string(), 2)),
+            pattern_of(35 * 36 + 35, 36, &Repeats::Primes)
+        );
+
+        assert_eq!(Ok(Repeats::AtLeast(3)), "3+".parse());
+        assert_eq!(Ok(Repeats::AnyOf(vec![2, 4])), "2,4".parse());
+        assert!("1".parse::<Repeats>().is_err());
+
+        // The sums agree with checking every ID
+        let input = vec![(1, 3000), (2000, 20000)];
+        for radix in [2, 3, 7, 10, 16, 36] {
+            for repeats in ["2", "3", "2+", "primes", "2,4"] {
+                let repeats: Repeats = repeats.parse().unwrap();
+                let params = Params {
+                    radix,
+                    repeats1: repeats.clone(),
+                    repeats2: repeats.clone(),
+                };
+                let brute = |ranges: &[(i64, i64)]| {
+                    ranges
+                        .iter()
+                        .flat_map(|&(start, end)| start..=end)
+                        .filter(|&id| pattern(id, radix, &repeats).is_some())
+                        .sum::<i64>() as i128
+                };
+                assert_eq!(brute(&input), part1(&input, &params));
+                assert_eq!(brute(&[(1, 20000)]), part2(&input, &params));
+            }
+        }
+    }
+
+    #[test]
+    fn test_explain() {
+        let params = Params::default();
+        let mut input = _sample_input();
+        input.push((20, 33));
+        let reports = explain(&input, &params);
+        let subtotals = reports.iter().map(|r| r.subtotal).sum::<i128>();
+        assert_eq!(part2(&input, &params), subtotals);
+
+        assert_eq!(
+            InvalidId {
+                id: 111,
+                pattern: Pattern {
+                    block: "1".to_string(),
+                    times: 3,
+                },
+                duplicate: false,
+            },
+            reports[1].invalid[1]
+        );
+        let last = reports.last().unwrap();
+        assert_eq!(
+            vec![22, 33],
+            last.invalid.iter().map(|i| i.id).collect::<Vec<_>>()
+        );
+        assert!(last.invalid[0].duplicate);
+        assert_eq!(33, last.subtotal);
+
+        let text = explanation_text(&reports);
+        assert!(text.starts_with("11-22\n  11 = 1 x 2\n  22 = 2 x 2\n  subtotal 33\n"));
+        assert!(text.contains("  22 = 2 x 2 (already counted)\n"));
+        let json = serde_json::to_value(&reports).unwrap();
+        assert_eq!(11, json[0]["invalid"][0]["id"]);
+        assert_eq!("1", json[0]["invalid"][0]["pattern"]["block"]);
+    }
+
+    #[test]
+    fn parse_errors() {
+        let lines = |data: &str| data.split('\n').map(|s| s.to_string()).collect();
+        assert!(process_input(lines("")).is_err());
+        assert!(process_input(lines("11-22,")).is_err());
+        assert!(process_input(lines("11-")).is_err());
+        assert!(process_input(lines("11-99999999999999999999")).is_err());
+        assert!(process_input(lines("11-22\n33-44")).is_err());
+    }
+
+    #[test]
+    fn differential() {
+        let size = Day02Size {
+            ranges: 4,
+            max_digits: 6,
+            max_width: 2000,
+        };
+        let params = Params::default();
+        check::differential(
+            200,
+            |rng| generate::day02(rng, &size).unwrap(),
+            process_input,
+            |input| (part1(input, &params), part2(input, &params)),
+            |input| {
+                (
+                    reference::day02::part1(input) as i128,
+                    reference::day02::part2(input) as i128,
+                )
+            },
+        );
     }
 
     #[test]
     fn real_part1() {
         let input = day_input();
-        assert_eq!(28844599675, part1(&input));
+        assert_eq!(28844599675, part1(&input, &Params::default()));
     }
 
     #[test]
     fn real_part2() {
         let input = day_input();
-        assert_eq!(48778605167, part2(&input));
+        assert_eq!(48778605167, part2(&input, &Params::default()));
     }
 }
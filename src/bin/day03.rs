@@ -1,18 +1,135 @@
-use aoc2025::common::read_input;
+use std::str::FromStr;
+
+use aoc2025::common::{Args, ParseError, read_input};
 
 const DAY: u32 = 3;
+// Largest divisor for `divisible:<m>`, where the search has a state for every
+// remainder
+const MAX_DIVISOR: u64 = 10_000;
+// Every joltage of up to 38 digits fits in a u128
+const MAX_DIGITS: usize = 38;
+// Most table entries the search may keep, about 128 MB
+const MAX_TABLE: usize = 4_000_000;
+
+// What the chosen batteries should make
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Objective {
+    // `max`
+    Max,
+    // `min`
+    Min,
+    // Largest joltage divisible by the number, `divisible:7`
+    DivisibleBy(u64),
+    // Joltage closest to the number, the smaller one on a tie, `closest:500`
+    ClosestTo(u128),
+}
+
+impl FromStr for Objective {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Objective, String> {
+        let divisor = |m: &str| {
+            m.parse::<u64>()
+                .map_err(|e| format!("Invalid divisor {m}: {e}"))
+        };
+        let target = |t: &str| {
+            t.parse::<u128>()
+                .map_err(|e| format!("Invalid target {t}: {e}"))
+        };
+        match s.split_once(':') {
+            None if s == "max" => Ok(Objective::Max),
+            None if s == "min" => Ok(Objective::Min),
+            Some(("divisible", m)) => match divisor(m)? {
+                0 => Err("Can't be divisible by 0".to_string()),
+                m if m > MAX_DIVISOR => Err(format!("Divisor {m} is larger than {MAX_DIVISOR}")),
+                m => Ok(Objective::DivisibleBy(m)),
+            },
+            Some(("closest", t)) => Ok(Objective::ClosestTo(target(t)?)),
+            _ => Err(format!(
+                "Expected max, min, divisible:<m> or closest:<target>, got {s}"
+            )),
+        }
+    }
+}
+
+// Puzzle parameters, overridable with `--param key=value`
+#[derive(Clone, Copy, Debug)]
+pub struct Params {
+    // Number of batteries to turn on in part 2
+    digits: usize,
+    objective: Objective,
+    // Batteries to leave off between two chosen ones
+    gap: usize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            digits: 12,
+            objective: Objective::Max,
+            gap: 0,
+        }
+    }
+}
+
+impl Params {
+    fn from_args(args: &Args) -> Result<Params, String> {
+        args.expect_params(&["digits", "objective", "gap"]);
+        let default = Params::default();
+        Params {
+            digits: args.param("digits", default.digits),
+            objective: args.param("objective", default.objective),
+            gap: args.param("gap", default.gap),
+        }
+        .checked()
+    }
+
+    // The parameters, if joltages fit in a u128 and the search tables fit in memory
+    fn checked(self) -> Result<Params, String> {
+        if self.digits > MAX_DIGITS {
+            return Err(format!(
+                "Joltages of more than {MAX_DIGITS} digits aren't supported, got {}",
+                self.digits
+            ));
+        }
+        let states = match self.objective {
+            Objective::Max | Objective::Min => 1,
+            Objective::DivisibleBy(m) => m as usize,
+            Objective::ClosestTo(_) => 2,
+        };
+        let table = table_size(self.digits, self.gap, states);
+        if table > MAX_TABLE {
+            return Err(format!(
+                "The search would need {table} table entries for {} digits, a gap of {} \
+                 and {states} states, more than {MAX_TABLE}",
+                self.digits, self.gap
+            ));
+        }
+        Ok(self)
+    }
+}
 
 pub fn main() {
+    let params =
+        Params::from_args(&Args::from_env()).unwrap_or_else(|e| panic!("Invalid parameters: {e}"));
     let input = day_input();
-    let part1_result = part1(&input);
+    let part1_result = part1(&input, &params);
     println!("Day {DAY}, part 1: {part1_result}");
-    let part2_result = part2(&input);
+    let part2_result = part2(&input, &params);
     println!("Day {DAY}, part 2: {part2_result}");
 }
 
 // Parse input
-fn process_input(input: Vec<String>) -> Vec<String> {
-    input
+pub fn process_input(input: Vec<String>) -> Result<Vec<String>, ParseError> {
+    for (i, bank) in input.iter().enumerate() {
+        if bank.len() < 2 {
+            return Err(ParseError::at(i, "A bank needs at least two batteries"));
+        }
+        if let Some(c) = bank.chars().find(|c| !c.is_ascii_digit()) {
+            return Err(ParseError::at(i, format!("Invalid battery {c:?}")));
+        }
+    }
+    Ok(input)
 }
 
 // Parse sample input
@@ -24,81 +141,347 @@ fn _sample_input() -> Vec<String> {
         .split('\n')
         .map(|s| s.to_string())
         .collect();
-    process_input(data)
+    process_input(data).unwrap()
 }
 
 // Parse day's input
 fn day_input() -> Vec<String> {
-    process_input(read_input(DAY))
+    process_input(read_input(DAY)).unwrap_or_else(|e| panic!("Invalid input: {e}"))
 }
 
-// Find the next digit for max number
-fn find_next(bank: &str, skip: usize, remaining: usize) -> (char, usize) {
-    let max = bank
-        .chars()
-        .skip(skip)
-        .take(bank.len() - remaining - skip)
-        .max()
-        .unwrap();
-    let index = bank.chars().skip(skip).position(|c| c == max).unwrap() + skip;
-    (max, index)
+// Largest number made of k batteries of the bank, kept in order. Each digit
+// pops the smaller ones before it off the stack, as long as enough batteries
+// are left to still pick k of them, so the stack holds the lexicographically
+// largest subsequence. O(n) for any k.
+pub fn largest_subsequence(bank: &[u8], k: usize) -> Vec<u8> {
+    let mut stack: Vec<u8> = Vec::with_capacity(bank.len());
+    // How many batteries can still be left out
+    let mut drops = bank.len() - k;
+    for &digit in bank {
+        while drops > 0 && stack.last().is_some_and(|&top| top < digit) {
+            stack.pop();
+            drops -= 1;
+        }
+        stack.push(digit);
+    }
+    stack.truncate(k);
+    stack
 }
 
-fn part1(input: &Vec<String>) -> i64 {
-    input
-        .iter()
-        .map(|bank| {
-            // Find first maximum digit, and then the next maximum digit after it
-            let max = bank.chars().take(bank.len() - 1).max().unwrap();
-            let first_index = bank.chars().position(|c| c == max).unwrap();
-            let second = bank.chars().skip(first_index + 1).max().unwrap();
-            max.to_digit(10).unwrap() as i64 * 10 + second.to_digit(10).unwrap() as i64
-        })
-        .sum()
+// Entries the search keeps for k digits at least `gap` apart, with the given
+// number of states: one row per position it looks ahead, plus the one it fills
+// and the one for the end of the bank
+fn table_size(k: usize, gap: usize, states: usize) -> usize {
+    gap.saturating_add(3)
+        .saturating_mul(k + 1)
+        .saturating_mul(states)
+}
+
+// Best value of k digits chosen from the bank, at least `gap` apart, by dynamic
+// programming over (position, digits left, state). The state carries what the
+// objective needs to know about the digits chosen so far: `step` gives the next
+// state after using a digit at a place value, or None if it can't be used
+// there, and the state after the last digit must be `accepted`. The first digit
+// starts from state 0. Of the values that work, the one `better` prefers wins.
+fn best_value(
+    bank: &[u8],
+    k: usize,
+    gap: usize,
+    states: usize,
+    step: impl Fn(usize, u128, u128) -> Option<usize>,
+    accepted: impl Fn(usize) -> bool,
+    better: impl Fn(u128, u128) -> bool,
+) -> Option<u128> {
+    let n = bank.len();
+    // Any gap past the end of the bank works the same as reaching the end
+    let gap = gap.min(n);
+    // A row holds the best values from bank[i..], at count * states + state
+    // for each number of digits left. Row i only needs rows i + 1 and
+    // i + 1 + gap, so just gap + 2 of them are kept, as a ring, along with the
+    // row for the end of the bank.
+    let mut end = vec![None; (k + 1) * states];
+    for (s, slot) in end.iter_mut().take(states).enumerate() {
+        *slot = accepted(s).then_some(0);
+    }
+    let mut rows = vec![end.clone(); gap + 2];
+    for i in (0..n).rev() {
+        let digit = (bank[i] - b'0') as u128;
+        let mut current = std::mem::take(&mut rows[i % (gap + 2)]);
+        let row = |j: usize| if j >= n { &end } else { &rows[j % (gap + 2)] };
+        let (after, after_gap) = (row(i + 1), row(i + 1 + gap));
+        current[..states].copy_from_slice(&end[..states]);
+        for count in 1..=k {
+            let place = 10u128.pow(count as u32 - 1);
+            for s in 0..states {
+                let skip = after[count * states + s];
+                let take = step(s, digit, place)
+                    .and_then(|s2| after_gap[(count - 1) * states + s2])
+                    .map(|rest| digit * place + rest);
+                current[count * states + s] = match (skip, take) {
+                    (Some(a), Some(b)) => Some(if better(b, a) { b } else { a }),
+                    (a, b) => a.or(b),
+                };
+            }
+        }
+        rows[i % (gap + 2)] = current;
+    }
+    if n == 0 {
+        end[k * states]
+    } else {
+        rows[0][k * states]
+    }
 }
 
-fn part2(input: &Vec<String>) -> i64 {
+// Joltage of k batteries of the bank for the objective, if any choice works
+pub fn joltage(bank: &str, k: usize, objective: Objective, gap: usize) -> Option<u128> {
+    let bank = bank.as_bytes();
+    assert!(
+        k <= MAX_DIGITS,
+        "Joltages of more than {MAX_DIGITS} digits aren't supported"
+    );
+    if k > bank.len() {
+        return None;
+    }
+    let greater = |a: u128, b: u128| a > b;
+    let less = |a: u128, b: u128| a < b;
+    match objective {
+        Objective::Max if gap == 0 => Some(
+            largest_subsequence(bank, k)
+                .iter()
+                .fold(0, |acc, digit| acc * 10 + (digit - b'0') as u128),
+        ),
+        Objective::Max => best_value(bank, k, gap, 1, |_, _, _| Some(0), |_| true, greater),
+        Objective::Min => best_value(bank, k, gap, 1, |_, _, _| Some(0), |_| true, less),
+        Objective::DivisibleBy(m) => {
+            assert!(
+                (1..=MAX_DIVISOR).contains(&m),
+                "Divisors must be between 1 and {MAX_DIVISOR}"
+            );
+            // State is the remainder the digits left must still make up
+            let m = m as u128;
+            let step = |r: usize, digit: u128, place: u128| {
+                let used = (digit * (place % m)) % m;
+                Some(((r as u128 + m - used) % m) as usize)
+            };
+            best_value(bank, k, gap, m as usize, step, |r| r == 0, greater)
+        }
+        Objective::ClosestTo(target) => {
+            if target >= 10u128.pow(k as u32) {
+                // Every joltage is below the target
+                return best_value(bank, k, gap, 1, |_, _, _| Some(0), |_| true, greater);
+            }
+            // Largest joltage at most the target, and smallest at least it,
+            // digit by digit. State 0 means the digits so far are the same as
+            // the target's, 1 that they already went below (or above) it.
+            let step = |below: bool| {
+                move |s: usize, digit: u128, place: u128| {
+                    let wanted = target / place % 10;
+                    if s == 1 || digit == wanted {
+                        Some(s)
+                    } else if (digit < wanted) == below {
+                        Some(1)
+                    } else {
+                        None
+                    }
+                }
+            };
+            let below = best_value(bank, k, gap, 2, step(true), |_| true, greater);
+            let above = best_value(bank, k, gap, 2, step(false), |_| true, less);
+            match (below, above) {
+                (Some(b), Some(a)) if a - target < target - b => Some(a),
+                (Some(b), _) => Some(b),
+                (None, a) => a,
+            }
+        }
+    }
+}
+
+// Joltage of the bank for the part, where banks that can't meet the objective
+// don't count
+fn bank_joltage(bank: &str, k: usize, params: &Params) -> i64 {
+    joltage(bank, k, params.objective, params.gap).map_or(0, |j| {
+        i64::try_from(j).unwrap_or_else(|_| panic!("Joltage {j} doesn't fit in an i64"))
+    })
+}
+
+pub fn part1(input: &[String], params: &Params) -> i64 {
+    input.iter().map(|bank| bank_joltage(bank, 2, params)).sum()
+}
+
+pub fn part2(input: &[String], params: &Params) -> i64 {
     input
         .iter()
-        .map(|bank| {
-            (0..12)
-                .rev()
-                .fold(("".to_string(), 0), |(acc, skip), r| {
-                    let (next, index) = find_next(bank, skip, r);
-                    (format!("{acc}{next}"), index + 1)
-                })
-                .0
-                .parse::<i64>()
-                .unwrap()
-        })
+        // Banks with too few batteries can't contribute
+        .filter(|bank| bank.len() >= params.digits)
+        .map(|bank| bank_joltage(bank, params.digits, params))
         .sum()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc2025::{
+        check,
+        generate::{self, Day03Size},
+        reference,
+    };
+    use itertools::Itertools;
 
     #[test]
     fn test_part1() {
         let input = _sample_input();
-        assert_eq!(357, part1(&input));
+        assert_eq!(357, part1(&input, &Params::default()));
     }
 
     #[test]
     fn test_part2() {
         let input = _sample_input();
-        assert_eq!(3121910778619, part2(&input));
+        assert_eq!(3121910778619, part2(&input, &Params::default()));
+    }
+
+    #[test]
+    fn test_part2_digits() {
+        let input = _sample_input();
+        let params = Params {
+            digits: 2,
+            ..Params::default()
+        };
+        assert_eq!(part1(&input, &params), part2(&input, &params));
+    }
+
+    #[test]
+    fn test_long_bank() {
+        let mut bank = "1".repeat(2_000_000);
+        bank.insert(1_000_000, '9');
+        bank.push('8');
+        assert_eq!(Some(98), joltage(&bank, 2, Objective::Max, 0));
+        assert_eq!(Some(911111111118), joltage(&bank, 12, Objective::Max, 0));
+        assert_eq!(b"432".to_vec(), largest_subsequence(b"1234321", 3));
+    }
+
+    #[test]
+    fn test_objectives() {
+        let bank = "818181911112111";
+        assert_eq!(Some(111), joltage(bank, 3, Objective::Min, 0));
+        assert_eq!(Some(921), joltage(bank, 3, Objective::Max, 2));
+        assert_eq!(Some(888), joltage(bank, 3, Objective::DivisibleBy(111), 0));
+        assert_eq!(Some(811), joltage(bank, 3, Objective::ClosestTo(800), 0));
+        assert_eq!(None, joltage("1111", 3, Objective::DivisibleBy(2), 0));
+        assert_eq!(Ok(Objective::DivisibleBy(7)), "divisible:7".parse());
+        assert_eq!(Ok(Objective::ClosestTo(500)), "closest:500".parse());
+        assert!("divisible:0".parse::<Objective>().is_err());
+        // Would wrap around to 0 as a u64
+        assert!(
+            "divisible:18446744073709551616"
+                .parse::<Objective>()
+                .is_err()
+        );
+        assert!("divisible:1000000000000".parse::<Objective>().is_err());
+        assert_eq!(
+            Ok(Objective::DivisibleBy(MAX_DIVISOR)),
+            format!("divisible:{MAX_DIVISOR}").parse()
+        );
+
+        // Same as trying every choice of batteries
+        let mut rng = generate::Rng::new(3);
+        for _ in 0..200 {
+            let len = rng.range(2, 9) as usize;
+            let bank = (0..len)
+                .map(|_| char::from(b'0' + rng.range(0, 9) as u8))
+                .collect::<String>();
+            let k = rng.range(1, len as i64) as usize;
+            let gap = rng.range(0, 2) as usize;
+            let choices = (0..len)
+                .combinations(k)
+                .filter(|c| c.windows(2).all(|w| w[1] - w[0] > gap))
+                .map(|c| c.iter().map(|&i| &bank[i..=i]).collect::<String>())
+                .map(|s| s.parse::<u128>().unwrap())
+                .collect::<Vec<_>>();
+            let target = rng.range(0, 10i64.pow(k as u32 + 1)) as u128;
+            let distance = |v: &u128| (v.abs_diff(target), *v);
+            let objectives = [
+                (Objective::Max, choices.iter().max().copied()),
+                (Objective::Min, choices.iter().min().copied()),
+                (
+                    Objective::DivisibleBy(7),
+                    choices.iter().filter(|v| *v % 7 == 0).max().copied(),
+                ),
+                (
+                    Objective::ClosestTo(target),
+                    choices.iter().min_by_key(|v| distance(v)).copied(),
+                ),
+            ];
+            for (objective, expected) in objectives {
+                assert_eq!(
+                    expected,
+                    joltage(&bank, k, objective, gap),
+                    "{bank} {k} {gap} {objective:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_params() {
+        let params = |digits, objective, gap| {
+            Params {
+                digits,
+                objective,
+                gap,
+            }
+            .checked()
+        };
+        assert!(params(MAX_DIGITS, Objective::Max, 0).is_ok());
+        assert!(params(MAX_DIGITS + 2, Objective::Max, 0).is_err());
+        assert!(params(12, Objective::DivisibleBy(MAX_DIVISOR), 20).is_ok());
+        assert_eq!(
+            Err(
+                "The search would need 5330000 table entries for 12 digits, a gap of 38 \
+                 and 10000 states, more than 4000000"
+                    .to_string()
+            ),
+            params(12, Objective::DivisibleBy(MAX_DIVISOR), 38).map(|p| p.digits)
+        );
+        assert!(params(12, Objective::Max, usize::MAX).is_err());
+    }
+
+    #[test]
+    fn parse_errors() {
+        let lines = |data: &str| data.split('\n').map(|s| s.to_string()).collect();
+        assert!(process_input(lines("12a4")).is_err());
+        assert!(process_input(lines("7")).is_err());
+    }
+
+    #[test]
+    fn differential() {
+        let size = Day03Size {
+            banks: 5,
+            length: 15,
+        };
+        let params = Params::default();
+        check::differential(
+            100,
+            |rng| generate::day03(rng, &size).unwrap(),
+            process_input,
+            |input| (part1(input, &params), part2(input, &params)),
+            |input| {
+                (
+                    reference::day03::total(input, 2),
+                    reference::day03::total(input, params.digits),
+                )
+            },
+        );
     }
 
     #[test]
     fn real_part1() {
         let input = day_input();
-        assert_eq!(17330, part1(&input));
+        assert_eq!(17330, part1(&input, &Params::default()));
     }
 
     #[test]
     fn real_part2() {
         let input = day_input();
-        assert_eq!(171518260283767, part2(&input));
+        assert_eq!(171518260283767, part2(&input, &Params::default()));
     }
 }
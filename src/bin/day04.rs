@@ -1,20 +1,146 @@
-use std::collections::HashMap;
+use std::{fs, path::Path, str::FromStr};
 
-use aoc2025::common::read_input;
+use aoc2025::{
+    common::{Args, ParseError, read_input},
+    render::{self, Image},
+};
 
 const DAY: u32 = 4;
 
+// Cells that count as neighbors, up to the radius away
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Neighborhood {
+    // The surrounding square, `moore`
+    Moore,
+    // The surrounding diamond, by Manhattan distance, `von-neumann`
+    VonNeumann,
+}
+
+impl FromStr for Neighborhood {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Neighborhood, String> {
+        match s {
+            "moore" => Ok(Neighborhood::Moore),
+            "von-neumann" => Ok(Neighborhood::VonNeumann),
+            _ => Err(format!("Expected moore or von-neumann, got {s}")),
+        }
+    }
+}
+
+// Puzzle parameters, overridable with `--param key=value`
+#[derive(Clone, Copy, Debug)]
+pub struct Params {
+    // Rolls with fewer neighbors than this are accessible
+    threshold: usize,
+    neighborhood: Neighborhood,
+    radius: usize,
+    // Whether the grid wraps around at the edges
+    wrap: bool,
+    roll: char,
+    empty: char,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            threshold: 4,
+            neighborhood: Neighborhood::Moore,
+            radius: 1,
+            wrap: false,
+            roll: '@',
+            empty: '.',
+        }
+    }
+}
+
+impl Params {
+    fn from_args(args: &Args) -> Params {
+        args.expect_params(&[
+            "threshold",
+            "neighborhood",
+            "radius",
+            "wrap",
+            "roll",
+            "empty",
+        ]);
+        let default = Params::default();
+        Params {
+            threshold: args.param("threshold", default.threshold),
+            neighborhood: args.param("neighborhood", default.neighborhood),
+            radius: args.param("radius", default.radius),
+            wrap: args.param("wrap", default.wrap),
+            roll: args.param("roll", default.roll),
+            empty: args.param("empty", default.empty),
+        }
+    }
+
+    // Offsets of the neighbors of a cell
+    fn offsets(&self) -> Vec<(i64, i64)> {
+        let r = self.radius as i64;
+        (-r..=r)
+            .flat_map(|dy| (-r..=r).map(move |dx| (dx, dy)))
+            .filter(|&(dx, dy)| (dx, dy) != (0, 0))
+            .filter(|&(dx, dy)| match self.neighborhood {
+                Neighborhood::Moore => true,
+                Neighborhood::VonNeumann => dx.abs() + dy.abs() <= r,
+            })
+            .collect()
+    }
+}
+
 pub fn main() {
-    let input = day_input();
-    let part1_result = part1(&input);
+    let args = Args::from_env();
+    args.expect_options(&["ppm", "gif", "scale"]);
+    let params = Params::from_args(&args);
+    let input = day_input(&params);
+    let ppm = args.value("ppm");
+    let gif = args.value("gif");
+    if ppm.is_some() || gif.is_some() {
+        // Pixels per cell
+        let scale: usize = args.value("scale").map_or(4, |s| {
+            s.parse()
+                .unwrap_or_else(|e| panic!("Invalid scale {s}: {e}"))
+        });
+        let grid = Grid::new(&input, &params);
+        let frames = render_rounds(&grid, &peel(&grid, params.threshold))
+            .iter()
+            .map(|frame| frame.scaled(scale))
+            .collect::<Vec<_>>();
+        if let Some(dir) = ppm {
+            write_ppms(Path::new(dir), &frames)
+                .unwrap_or_else(|e| panic!("Could not write images to {dir}: {e}"));
+        }
+        if let Some(path) = gif {
+            render::write_gif(path, &frames, 20)
+                .unwrap_or_else(|e| panic!("Could not write {path}: {e}"));
+        }
+    }
+    let part1_result = part1(&input, &params);
     println!("Day {DAY}, part 1: {part1_result}");
-    let part2_result = part2(&input);
+    let part2_result = part2(&input, &params);
     println!("Day {DAY}, part 2: {part2_result}");
 }
 
 // Parse input
-fn process_input(input: Vec<String>) -> Vec<String> {
-    input
+pub fn process_input(input: Vec<String>) -> Result<Vec<String>, ParseError> {
+    process_input_with(input, &Params::default())
+}
+
+// Parse input with the symbols of the parameters
+pub fn process_input_with(input: Vec<String>, params: &Params) -> Result<Vec<String>, ParseError> {
+    for (i, row) in input.iter().enumerate() {
+        if row.chars().count() != input[0].chars().count() {
+            return Err(ParseError::at(i, "Rows have different lengths"));
+        }
+        if let Some(c) = row
+            .chars()
+            .find(|c| *c != params.roll && *c != params.empty)
+        {
+            return Err(ParseError::at(i, format!("Unknown symbol {c:?}")));
+        }
+    }
+    Ok(input)
 }
 
 // Parse sample input
@@ -32,125 +158,374 @@ fn _sample_input() -> Vec<String> {
         .split('\n')
         .map(|s| s.to_string())
         .collect();
-    process_input(data)
+    process_input(data).unwrap()
 }
 
 // Parse day's input
-fn day_input() -> Vec<String> {
-    process_input(read_input(DAY))
-}
-
-fn neighbors(x: usize, y: usize, map: &Vec<String>, removed: &Vec<(i64, i64)>) -> Vec<(i64, i64)> {
-    let mut result: Vec<(i64, i64)> = Vec::new();
-    let height = map.len() as i64;
-    let width = map[0].len() as i64;
-    let x = x as i64;
-    let y = y as i64;
-    for dy in -1..=1 {
-        for dx in -1..=1 {
-            if dx == 0 && dy == 0 {
-                continue;
+fn day_input(params: &Params) -> Vec<String> {
+    process_input_with(read_input(DAY), params).unwrap_or_else(|e| panic!("Invalid input: {e}"))
+}
+
+// Paper rolls as a dense grid, indexed by y * width + x
+pub struct Grid {
+    width: usize,
+    height: usize,
+    rolls: Vec<bool>,
+    offsets: Vec<(i64, i64)>,
+    wrap: bool,
+}
+
+impl Grid {
+    pub fn new(input: &[String], params: &Params) -> Grid {
+        Grid {
+            width: input.first().map_or(0, |row| row.chars().count()),
+            height: input.len(),
+            rolls: input
+                .iter()
+                .flat_map(|row| row.chars().map(|c| c == params.roll))
+                .collect(),
+            offsets: params.offsets(),
+            wrap: params.wrap,
+        }
+    }
+
+    // Cells around the given one. On a wrapping grid smaller than the
+    // neighborhood, the same cell can be a neighbor more than once.
+    fn neighbors(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        let (width, height) = (self.width as i64, self.height as i64);
+        let (x, y) = (index as i64 % width, index as i64 / width);
+        self.offsets.iter().filter_map(move |&(dx, dy)| {
+            let (mut nx, mut ny) = (x + dx, y + dy);
+            if self.wrap {
+                (nx, ny) = (nx.rem_euclid(width), ny.rem_euclid(height));
             }
-            let nx = x + dx;
-            let ny = y + dy;
-            if nx >= 0 && nx < width && ny >= 0 && ny < height && !removed.contains(&(nx, ny)) {
-                let symbol = map
-                    .get(ny as usize)
-                    .unwrap()
-                    .chars()
-                    .nth(nx as usize)
-                    .unwrap();
-                if symbol == '@' {
-                    result.push((nx, ny));
+            let inside = (0..width).contains(&nx) && (0..height).contains(&ny);
+            let n = (ny * width + nx) as usize;
+            (inside && n != index).then_some(n)
+        })
+    }
+
+    // Number of rolls around each cell
+    fn roll_counts(&self) -> Vec<usize> {
+        (0..self.rolls.len())
+            .map(|i| self.neighbors(i).filter(|&n| self.rolls[n]).count())
+            .collect()
+    }
+}
+
+// Which round of forklift access removed each roll
+pub struct Peeling {
+    width: usize,
+    // Round for each cell, starting from 1, or None if it was never removed
+    rounds: Vec<Option<u32>>,
+    // Cells removed on each round
+    history: Vec<Vec<usize>>,
+}
+
+impl Peeling {
+    pub fn round(&self, x: usize, y: usize) -> Option<u32> {
+        self.rounds[y * self.width + x]
+    }
+
+    pub fn removed(&self) -> usize {
+        self.history.iter().map(|cells| cells.len()).sum()
+    }
+
+    // Number of rounds that removed something
+    pub fn rounds(&self) -> u32 {
+        self.history.len() as u32
+    }
+
+    // Rolls removed on each round, as (x, y)
+    pub fn history(&self) -> impl Iterator<Item = Vec<(usize, usize)>> + '_ {
+        self.history.iter().map(|cells| {
+            cells
+                .iter()
+                .map(|i| (i % self.width, i / self.width))
+                .collect()
+        })
+    }
+}
+
+// Remove accessible rolls round by round, like the forklifts do. Instead of
+// rescanning the grid, each roll keeps a count of its neighbors, and only the
+// neighbors of removed rolls can become accessible on the next round. Every
+// roll is removed at most once, so this is linear in the size of the grid.
+pub fn peel(grid: &Grid, threshold: usize) -> Peeling {
+    let mut counts = grid.roll_counts();
+    let mut rounds = vec![None; grid.rolls.len()];
+    let mut history = Vec::new();
+    let mut current = (0..grid.rolls.len())
+        .filter(|&i| grid.rolls[i] && counts[i] < threshold)
+        .collect::<Vec<_>>();
+    while !current.is_empty() {
+        let round = history.len() as u32 + 1;
+        current.iter().for_each(|&i| rounds[i] = Some(round));
+        let mut next = Vec::new();
+        for &i in &current {
+            for n in grid.neighbors(i) {
+                if !grid.rolls[n] || rounds[n].is_some() {
+                    continue;
+                }
+                counts[n] -= 1;
+                // Became accessible just now, so it isn't queued yet
+                if counts[n] + 1 == threshold {
+                    next.push(n);
                 }
             }
         }
+        history.push(current);
+        current = next;
     }
-    //println!("Neighbors of ({},{}) : {:?}", x, y, result);
-    result
-}
-
-fn part1(input: &Vec<String>) -> i64 {
-    let removed = Vec::new();
-    (0..input.len())
-        .map(|y| {
-            (0..input[0].len())
-                .filter_map(|x| {
-                    let symbol = input[y].chars().nth(x).unwrap();
-                    if symbol == '@' {
-                        Some(neighbors(x, y, input, &removed).len())
-                    } else {
-                        None
-                    }
-                })
-                .filter(|n| *n < 4)
-                .count() as i64
-        })
-        .sum()
-}
-
-// A bit slow but works (~8.5s on debug build, ~0.5s on release)
-fn part2(input: &Vec<String>) -> i64 {
-    let mut rolls = HashMap::new();
-    let mut removed = 0;
-    // Initialize neighbor map
-    (0..input.len()).for_each(|y| {
-        (0..input[0].len()).for_each(|x| {
-            let symbol = input[y].chars().nth(x).unwrap();
-            if symbol == '@' {
-                let n = neighbors(x, y, input, &Vec::new());
-                rolls.insert((x as i64, y as i64), n);
-            }
-        });
-    });
+    Peeling {
+        width: grid.width,
+        rounds,
+        history,
+    }
+}
 
-    loop {
-        // Find all with less than 4 neighbors
-        let to_remove = rolls
-            .iter()
-            .filter_map(|(pair, n)| if n.len() < 4 { Some(*pair) } else { None })
-            .collect::<Vec<_>>();
-        if to_remove.is_empty() {
-            break;
-        }
-        removed += to_remove.len() as i64;
+// The grid after the given round, with the removed rolls colored by the round
+// that removed them, the remaining rolls white and the empty cells black
+pub fn render_round(grid: &Grid, peeling: &Peeling, round: u32) -> Image {
+    let mut image = Image::new(grid.width, grid.height, render::BLACK);
+    for i in 0..grid.rolls.len() {
+        let (x, y) = (i % grid.width, i / grid.width);
+        let color = match peeling.rounds[i] {
+            Some(removed) if removed <= round => {
+                let last = peeling.rounds().max(2) - 1;
+                render::gradient((removed - 1) as f64 / last as f64)
+            }
+            _ if grid.rolls[i] => render::WHITE,
+            _ => render::BLACK,
+        };
+        image.set(x, y, color);
+    }
+    image
+}
 
-        // Remove them from all neighbor lists
-        rolls.values_mut().for_each(|v| {
-            v.retain(|p| !to_remove.contains(p));
-        });
+// The initial grid, and the grid after every round
+pub fn render_rounds(grid: &Grid, peeling: &Peeling) -> Vec<Image> {
+    (0..=peeling.rounds())
+        .map(|round| render_round(grid, peeling, round))
+        .collect()
+}
 
-        // Remove them from rolls
-        rolls.retain(|k, _| !to_remove.contains(k));
+// One numbered PPM file per round
+fn write_ppms(dir: &Path, frames: &[Image]) -> std::io::Result<()> {
+    fs::create_dir_all(dir)?;
+    for (round, frame) in frames.iter().enumerate() {
+        frame.write_ppm(dir.join(format!("round{round:04}.ppm")))?;
     }
-    removed
+    Ok(())
+}
+
+pub fn part1(input: &[String], params: &Params) -> i64 {
+    let grid = Grid::new(input, params);
+    let counts = grid.roll_counts();
+    (0..grid.rolls.len())
+        .filter(|&i| grid.rolls[i] && counts[i] < params.threshold)
+        .count() as i64
+}
+
+pub fn part2(input: &[String], params: &Params) -> i64 {
+    peel(&Grid::new(input, params), params.threshold).removed() as i64
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc2025::{
+        check,
+        generate::{self, Day04Size},
+        reference,
+    };
 
     #[test]
     fn test_part1() {
         let input = _sample_input();
-        assert_eq!(13, part1(&input));
+        assert_eq!(13, part1(&input, &Params::default()));
     }
 
     #[test]
     fn test_part2() {
         let input = _sample_input();
-        assert_eq!(43, part2(&input));
+        assert_eq!(43, part2(&input, &Params::default()));
+    }
+
+    #[test]
+    fn test_peeling() {
+        let input = _sample_input();
+        let peeling = peel(&Grid::new(&input, &Params::default()), 4);
+        let per_round = peeling
+            .history()
+            .map(|cells| cells.len())
+            .collect::<Vec<_>>();
+        assert_eq!(vec![13, 12, 7, 5, 2, 1, 1, 1, 1], per_round);
+        assert_eq!(Some(1), peeling.round(2, 0));
+        assert_eq!(Some(3), peeling.round(1, 1));
+        // Empty cell, and a roll that is never accessible
+        assert_eq!(None, peeling.round(0, 0));
+        assert_eq!(None, peeling.round(4, 4));
+    }
+
+    #[test]
+    fn test_rules() {
+        let rounds = |input: &str, params: &Params| {
+            let input =
+                process_input_with(input.lines().map(|s| s.to_string()).collect(), params).unwrap();
+            let peeling = peel(&Grid::new(&input, params), params.threshold);
+            let width = input[0].chars().count();
+            (0..input.len() * width)
+                .map(|i| peeling.round(i % width, i / width).unwrap_or(0))
+                .collect::<Vec<_>>()
+        };
+        let von_neumann = Params {
+            neighborhood: Neighborhood::VonNeumann,
+            ..Params::default()
+        };
+        assert_eq!(
+            vec![1, 1, 1, 1, 2, 1, 1, 1, 1],
+            rounds("@@@\n@@@\n@@@", &von_neumann)
+        );
+        let wrapping = Params {
+            wrap: true,
+            ..von_neumann
+        };
+        assert_eq!(vec![0; 9], rounds("@@@\n@@@\n@@@", &wrapping));
+        let custom = Params {
+            radius: 2,
+            threshold: 9,
+            roll: '#',
+            empty: ' ',
+            ..Params::default()
+        };
+        assert_eq!(
+            vec![1, 0, 0, 0, 1, 1, 0, 1, 1],
+            rounds("#  \n ##\n ##", &custom)
+        );
+        assert!(process_input_with(vec!["#@".to_string()], &custom).is_err());
+
+        // Same as recounting every roll's neighbors on every round
+        let mut rng = generate::Rng::new(4);
+        for _ in 0..100 {
+            let (width, height) = (rng.range(1, 7) as usize, rng.range(1, 7) as usize);
+            let params = Params {
+                threshold: rng.range(0, 9) as usize,
+                neighborhood: *[Neighborhood::Moore, Neighborhood::VonNeumann]
+                    .get(rng.index(2))
+                    .unwrap(),
+                radius: rng.range(0, 3) as usize,
+                wrap: rng.chance(0.5),
+                ..Params::default()
+            };
+            let mut grid = (0..height)
+                .map(|_| (0..width).map(|_| rng.chance(0.7)).collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            let input = grid
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|&r| if r { '@' } else { '.' })
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>();
+            let mut expected = vec![0; width * height];
+            for round in 1.. {
+                let count = |x: usize, y: usize, grid: &[Vec<bool>]| {
+                    let mut seen = 0;
+                    for (dx, dy) in params.offsets() {
+                        let (nx, ny) = (x as i64 + dx, y as i64 + dy);
+                        let (nx, ny) = if params.wrap {
+                            (nx.rem_euclid(width as i64), ny.rem_euclid(height as i64))
+                        } else {
+                            (nx, ny)
+                        };
+                        if (nx, ny) != (x as i64, y as i64)
+                            && (0..width as i64).contains(&nx)
+                            && (0..height as i64).contains(&ny)
+                            && grid[ny as usize][nx as usize]
+                        {
+                            seen += 1;
+                        }
+                    }
+                    seen
+                };
+                let accessible = (0..width * height)
+                    .filter(|i| grid[i / width][i % width])
+                    .filter(|i| count(i % width, i / width, &grid) < params.threshold)
+                    .collect::<Vec<_>>();
+                if accessible.is_empty() {
+                    break;
+                }
+                for i in accessible {
+                    grid[i / width][i % width] = false;
+                    expected[i] = round;
+                }
+            }
+            let peeling = peel(&Grid::new(&input, &params), params.threshold);
+            let actual = (0..width * height)
+                .map(|i| peeling.round(i % width, i / width).unwrap_or(0))
+                .collect::<Vec<_>>();
+            assert_eq!(expected, actual, "{params:?}\n{}", input.join("\n"));
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let input = _sample_input();
+        let grid = Grid::new(&input, &Params::default());
+        let peeling = peel(&grid, 4);
+        let frames = render_rounds(&grid, &peeling);
+        assert_eq!(10, frames.len());
+        assert_eq!(render::WHITE, frames[0].get(2, 0));
+        assert_eq!(render::gradient(0.0), frames[1].get(2, 0));
+        assert_eq!(render::gradient(0.25), frames[3].get(1, 1));
+        assert_eq!(render::WHITE, frames[9].get(4, 4));
+        assert_eq!(render::BLACK, frames[9].get(0, 0));
+        let last = frames.last().unwrap().scaled(2);
+        assert_eq!((20, 20), (last.width(), last.height()));
+        assert!(last.to_ppm().starts_with(b"P6\n20 20\n255\n"));
+        assert_eq!(13 + 20 * 20 * 3, last.to_ppm().len());
+    }
+
+    #[test]
+    fn parse_errors() {
+        let lines = |data: &str| data.split('\n').map(|s| s.to_string()).collect();
+        assert!(process_input(lines("@.@\n@.")).is_err());
+        assert!(process_input(lines("@#")).is_err());
+    }
+
+    #[test]
+    fn differential() {
+        let size = Day04Size {
+            width: 12,
+            height: 10,
+            density: 0.7,
+        };
+        let params = Params::default();
+        check::differential(
+            100,
+            |rng| generate::day04(rng, &size).unwrap(),
+            process_input,
+            |input| (part1(input, &params), part2(input, &params)),
+            |input| {
+                (
+                    reference::day04::part1(input, params.threshold),
+                    reference::day04::part2(input, params.threshold),
+                )
+            },
+        );
     }
 
     #[test]
     fn real_part1() {
-        let input = day_input();
-        assert_eq!(1367, part1(&input));
+        let input = day_input(&Params::default());
+        assert_eq!(1367, part1(&input, &Params::default()));
     }
 
     #[test]
     fn real_part2() {
-        let input = day_input();
-        assert_eq!(9144, part2(&input));
+        let input = day_input(&Params::default());
+        assert_eq!(9144, part2(&input, &Params::default()));
     }
 }
@@ -1,36 +1,87 @@
-use aoc2025::common::read_input;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    io::{BufRead, IsTerminal, Write, stdin, stdout},
+};
+
+use aoc2025::common::{Args, ParseError, read_input};
+
+// Parsed puzzle input
+pub type Input = (Vec<(i64, i64)>, Vec<i64>);
 
 const DAY: u32 = 5;
 
+// Puzzle parameters, overridable with `--param key=value`. This puzzle has no
+// constants worth varying, so there are none, but typos are still caught.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Params {}
+
+impl Params {
+    fn from_args(args: &Args) -> Params {
+        args.expect_params(&[]);
+        Params {}
+    }
+}
+
 pub fn main() {
+    let args = Args::from_env();
+    args.expect_options(&["query", "diagnose", "normalize"]);
+    let params = Params::from_args(&args);
     let input = day_input();
-    let part1_result = part1(&input);
+    if args.flag("diagnose") {
+        print!("{}", diagnostics_text(&input.0));
+    }
+    if let Some(path) = args.value("normalize") {
+        fs::write(path, normalized(&input.0))
+            .unwrap_or_else(|e| panic!("Could not write {path}: {e}"));
+    }
+    if args.flag("query") {
+        let mut inventory = Inventory::new(&input.0);
+        match args.option("query") {
+            Some(path) => {
+                let queries = fs::read_to_string(path)
+                    .unwrap_or_else(|e| panic!("Could not read {path}: {e}"));
+                queries
+                    .lines()
+                    .for_each(|query| println!("{}", answer(&mut inventory, query)));
+            }
+            None => repl(&mut inventory),
+        }
+        return;
+    }
+    let part1_result = part1(&input, &params);
     println!("Day {DAY}, part 1: {part1_result}");
-    let part2_result = part2(&input);
+    let part2_result = part2(&input, &params);
     println!("Day {DAY}, part 2: {part2_result}");
 }
 
 // Parse input
-fn process_input(input: Vec<String>) -> (Vec<(i64, i64)>, Vec<i64>) {
+pub fn process_input(input: Vec<String>) -> Result<Input, ParseError> {
     let range_pattern = regex::Regex::new(r"^(\d+)-(\d+)$").unwrap();
     let number_pattern = regex::Regex::new(r"^(\d+)$").unwrap();
     let mut ranges: Vec<(i64, i64)> = Vec::new();
     let mut numbers: Vec<i64> = Vec::new();
-    input.iter().for_each(|row| {
+    for (i, row) in input.iter().enumerate() {
+        let parse = |s: &str| {
+            s.parse::<i64>()
+                .map_err(|e| ParseError::at(i, format!("Invalid ID {s}: {e}")))
+        };
         if let Some(caps) = range_pattern.captures(row) {
-            let start: i64 = caps.get(1).unwrap().as_str().parse().unwrap();
-            let end: i64 = caps.get(2).unwrap().as_str().parse().unwrap();
-            ranges.push((start, end));
+            ranges.push((parse(&caps[1])?, parse(&caps[2])?));
         } else if let Some(caps) = number_pattern.captures(row) {
-            let number: i64 = caps.get(1).unwrap().as_str().parse().unwrap();
-            numbers.push(number);
+            numbers.push(parse(&caps[1])?);
+        } else if !row.is_empty() {
+            return Err(ParseError::at(
+                i,
+                format!("Expected a range or an ID, got {row}"),
+            ));
         }
-    });
-    (ranges, numbers)
+    }
+    Ok((ranges, numbers))
 }
 
 // Parse sample input
-fn _sample_input() -> (Vec<(i64, i64)>, Vec<i64>) {
+fn _sample_input() -> Input {
     let data = "3-5
 10-14
 16-20
@@ -45,78 +96,586 @@ fn _sample_input() -> (Vec<(i64, i64)>, Vec<i64>) {
     .split('\n')
     .map(|s| s.to_string())
     .collect();
-    process_input(data)
+    process_input(data).unwrap()
 }
 
 // Parse day's input
-fn day_input() -> (Vec<(i64, i64)>, Vec<i64>) {
-    process_input(read_input(DAY))
+fn day_input() -> Input {
+    process_input(read_input(DAY)).unwrap_or_else(|e| panic!("Invalid input: {e}"))
 }
 
-fn part1(input: &(Vec<(i64, i64)>, Vec<i64>)) -> i64 {
-    let (ranges, numbers) = input;
-    numbers
-        .iter()
-        .filter(|n| {
-            ranges
-                .iter()
-                .any(|(start, end)| **n >= *start && **n <= *end)
-        })
-        .count() as i64
+// How many ranges cover each ID, stored as the count from each breakpoint up
+// to the next one. IDs before the first breakpoint aren't covered, and the
+// last breakpoint always has a count of zero. Breakpoints are i128 so the one
+// after a range ending at i64::MAX still fits.
+#[derive(Clone, Debug, Default)]
+pub struct Coverage {
+    counts: BTreeMap<i128, u32>,
+    total: i128,
 }
 
-fn part2(input: &(Vec<(i64, i64)>, Vec<i64>)) -> i64 {
-    let (ranges, _) = input;
+impl Coverage {
+    // Count of the segment containing the ID
+    fn count_at(&self, id: i128) -> u32 {
+        self.counts.range(..=id).next_back().map_or(0, |(_, &c)| c)
+    }
 
-    // Sort ranges and merge overlapping ones
-    let mut merged_ranges: Vec<(i64, i64)> = Vec::new();
-    let mut sorted_ranges = ranges.clone();
-    sorted_ranges.sort_by(|a, b| a.0.cmp(&b.0));
-    for range in sorted_ranges {
-        if let Some(last) = merged_ranges.last_mut() {
-            if range.0 <= last.1 {
-                last.1 = last.1.max(range.1);
-            } else {
-                merged_ranges.push(range);
+    // Make sure a segment starts at the ID
+    fn split(&mut self, id: i128) {
+        if !self.counts.contains_key(&id) {
+            let count = self.count_at(id);
+            self.counts.insert(id, count);
+        }
+    }
+
+    // Drop the breakpoint if it doesn't change the count
+    fn join(&mut self, id: i128) {
+        let before = self.count_at(id - 1);
+        if self.counts.get(&id) == Some(&before) {
+            self.counts.remove(&id);
+        }
+    }
+
+    // Add `delta` to the count of every ID in start..=end, keeping the number
+    // of covered IDs up to date
+    fn update(&mut self, start: i64, end: i64, delta: i32) {
+        if start > end {
+            return;
+        }
+        let (start, after) = (start as i128, end as i128 + 1);
+        self.split(start);
+        self.split(after);
+        let keys = self
+            .counts
+            .range(start..=after)
+            .map(|(&k, _)| k)
+            .collect::<Vec<_>>();
+        for w in keys.windows(2) {
+            let count = self.counts.get_mut(&w[0]).unwrap();
+            let was_covered = *count > 0;
+            *count = count.checked_add_signed(delta).unwrap();
+            match (was_covered, *count > 0) {
+                (false, true) => self.total += w[1] - w[0],
+                (true, false) => self.total -= w[1] - w[0],
+                _ => {}
+            }
+        }
+        self.join(start);
+        self.join(after);
+    }
+
+    // Number of covered IDs
+    pub fn total(&self) -> i128 {
+        self.total
+    }
+
+    pub fn is_covered(&self, id: i64) -> bool {
+        self.count_at(id as i128) > 0
+    }
+
+    // Covered segments overlapping low..=high, clipped to it, with touching
+    // segments joined together
+    pub fn covered(&self, low: i64, high: i64) -> Vec<(i64, i64)> {
+        if low > high {
+            return Vec::new();
+        }
+        let (low, high) = (low as i128, high as i128);
+        let first = self
+            .counts
+            .range(..=low)
+            .next_back()
+            .map_or(low, |(&k, _)| k);
+        let mut covered: Vec<(i64, i64)> = Vec::new();
+        let segments = self
+            .counts
+            .range(first..)
+            .zip(self.counts.range(first..).skip(1));
+        for ((&start, &count), (&next, _)) in segments {
+            if start > high {
+                break;
             }
+            if count == 0 {
+                continue;
+            }
+            let (start, end) = (start.max(low) as i64, (next - 1).min(high) as i64);
+            if start > end {
+                continue;
+            }
+            match covered.last_mut() {
+                Some(last) if last.1 as i128 + 1 == start as i128 => last.1 = end,
+                _ => covered.push((start, end)),
+            }
+        }
+        covered
+    }
+}
+
+// Fresh ID ranges, which can be added and removed while keeping track of
+// which IDs are fresh
+#[derive(Clone, Debug, Default)]
+pub struct Inventory {
+    ranges: Vec<(i64, i64)>,
+    coverage: Coverage,
+}
+
+impl Inventory {
+    pub fn new(ranges: &[(i64, i64)]) -> Inventory {
+        let mut inventory = Inventory::default();
+        ranges
+            .iter()
+            .for_each(|&(start, end)| inventory.add(start, end));
+        inventory
+    }
+
+    // Ranges that end before they start are kept, but don't contain any IDs
+    pub fn add(&mut self, start: i64, end: i64) {
+        self.ranges.push((start, end));
+        self.coverage.update(start, end, 1);
+    }
+
+    // Remove one copy of a range, or return false if there is none
+    pub fn remove(&mut self, start: i64, end: i64) -> bool {
+        let Some(i) = self.ranges.iter().position(|&r| r == (start, end)) else {
+            return false;
+        };
+        self.ranges.swap_remove(i);
+        self.coverage.update(start, end, -1);
+        true
+    }
+
+    pub fn ranges(&self) -> &[(i64, i64)] {
+        &self.ranges
+    }
+
+    // Sorted, disjoint and non-touching ranges with the same fresh IDs
+    pub fn merged(&self) -> Vec<(i64, i64)> {
+        self.coverage.covered(i64::MIN, i64::MAX)
+    }
+
+    pub fn is_fresh(&self, id: i64) -> bool {
+        self.coverage.is_covered(id)
+    }
+
+    // Original ranges containing the ID
+    pub fn covering(&self, id: i64) -> Vec<(i64, i64)> {
+        self.ranges
+            .iter()
+            .filter(|&&(start, end)| start <= id && id <= end)
+            .copied()
+            .collect()
+    }
+
+    // Smallest fresh ID after the given one
+    pub fn next_fresh(&self, id: i64) -> Option<i64> {
+        let after = id.checked_add(1)?;
+        let &(start, _) = self.coverage.covered(after, i64::MAX).first()?;
+        Some(start)
+    }
+
+    // Number of fresh IDs in low..=high, which is empty if low > high
+    pub fn count_fresh(&self, low: i64, high: i64) -> i128 {
+        self.coverage
+            .covered(low, high)
+            .iter()
+            .map(|&(start, end)| end as i128 - start as i128 + 1)
+            .sum()
+    }
+
+    pub fn total(&self) -> i128 {
+        self.coverage.total()
+    }
+
+    // Spoiled IDs between the fresh ranges
+    pub fn gaps(&self) -> Vec<(i64, i64)> {
+        self.merged()
+            .windows(2)
+            .map(|w| (w[0].1 + 1, w[1].0 - 1))
+            .collect()
+    }
+}
+
+// Something odd about a range, referring to ranges by their position in the
+// input
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Issue {
+    // Start is after the end, so the range is empty
+    Inverted(usize),
+    // Same as an earlier range
+    Duplicate(usize, usize),
+    // Every ID is also in another range
+    Contained(usize, usize),
+    // Ends right before another range starts, so they merge without overlapping
+    Adjacent(usize, usize),
+}
+
+// Find every issue with the ranges, in the order of the ranges involved
+pub fn diagnose(ranges: &[(i64, i64)]) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let mut starts: HashMap<i64, Vec<usize>> = HashMap::new();
+    let mut seen: HashMap<(i64, i64), usize> = HashMap::new();
+    for (i, &(start, end)) in ranges.iter().enumerate() {
+        if start > end {
+            issues.push(Issue::Inverted(i));
+        } else if let Some(&first) = seen.get(&(start, end)) {
+            issues.push(Issue::Duplicate(i, first));
         } else {
-            merged_ranges.push(range);
+            seen.insert((start, end), i);
+            starts.entry(start).or_default().push(i);
+        }
+    }
+
+    // Sorted by start, with longer ranges first, every range is contained in
+    // the one reaching furthest so far (if it reaches far enough)
+    let mut order = seen.values().copied().collect::<Vec<_>>();
+    order.sort_by_key(|&i| (ranges[i].0, -(ranges[i].1 as i128), i));
+    let mut furthest: Option<usize> = None;
+    for i in order {
+        match furthest {
+            Some(j) if ranges[j].1 >= ranges[i].1 => issues.push(Issue::Contained(i, j)),
+            _ => furthest = Some(i),
+        }
+    }
+
+    for (i, &(start, end)) in ranges.iter().enumerate() {
+        if seen.get(&(start, end)) != Some(&i) {
+            continue;
         }
+        let next = end.checked_add(1).and_then(|next| starts.get(&next));
+        issues.extend(next.into_iter().flatten().map(|&j| Issue::Adjacent(i, j)));
     }
+    issues.sort_by_key(|&issue| match issue {
+        Issue::Inverted(i) => (i, 0),
+        Issue::Duplicate(i, j) | Issue::Contained(i, j) | Issue::Adjacent(i, j) => (i, j),
+    });
+    issues
+}
 
-    // Calculate fresh ingredients
-    merged_ranges
+// Human readable report of the issues, with a summary of what merging does
+pub fn diagnostics_text(ranges: &[(i64, i64)]) -> String {
+    let show = |i: usize| format!("#{} {}-{}", i + 1, ranges[i].0, ranges[i].1);
+    let issues = diagnose(ranges);
+    let mut text = String::new();
+    for issue in &issues {
+        let line = match *issue {
+            Issue::Inverted(i) => format!("{} is inverted and contains no IDs", show(i)),
+            Issue::Duplicate(i, j) => format!("{} duplicates {}", show(i), show(j)),
+            Issue::Contained(i, j) => format!("{} is contained in {}", show(i), show(j)),
+            Issue::Adjacent(i, j) => format!("{} is adjacent to {}", show(i), show(j)),
+        };
+        text.push_str(&line);
+        text.push('\n');
+    }
+    let inventory = Inventory::new(ranges);
+    text.push_str(&format!(
+        "{} issues, {} ranges merge into {} with {} fresh IDs\n",
+        issues.len(),
+        ranges.len(),
+        inventory.merged().len(),
+        inventory.total()
+    ));
+    text
+}
+
+// The fewest ranges covering the same IDs, in the input format
+pub fn normalized(ranges: &[(i64, i64)]) -> String {
+    Inventory::new(ranges)
+        .merged()
         .iter()
-        .map(|(start, end)| end - start + 1)
-        .sum()
+        .map(|(start, end)| format!("{start}-{end}\n"))
+        .collect()
+}
+
+// Answer a single query, or explain what went wrong
+pub fn answer(inventory: &mut Inventory, query: &str) -> String {
+    let words = query.split_whitespace().collect::<Vec<_>>();
+    let ids = words
+        .iter()
+        .skip(1)
+        .map(|w| w.parse::<i64>())
+        .collect::<Result<Vec<_>, _>>();
+    let show = |ranges: &[(i64, i64)]| {
+        ranges
+            .iter()
+            .map(|(start, end)| format!("{start}-{end}"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    match (words.first().copied(), ids.as_deref()) {
+        (Some("fresh"), Ok(&[id])) => {
+            let state = if inventory.is_fresh(id) {
+                "fresh"
+            } else {
+                "spoiled"
+            };
+            format!("{id} is {state}")
+        }
+        (Some("covers"), Ok(&[id])) => match inventory.covering(id) {
+            ranges if ranges.is_empty() => format!("{id} is not in any range"),
+            ranges => format!("{id} is in {}", show(&ranges)),
+        },
+        (Some("next"), Ok(&[id])) => match inventory.next_fresh(id) {
+            Some(next) => format!("Next fresh ID after {id} is {next}"),
+            None => format!("No fresh IDs after {id}"),
+        },
+        (Some("count"), Ok(&[low, high])) => format!(
+            "{} fresh IDs in {low}-{high}",
+            inventory.count_fresh(low, high)
+        ),
+        (Some("add"), Ok(&[start, end])) => {
+            inventory.add(start, end);
+            format!("Added {start}-{end}, {} fresh IDs", inventory.total())
+        }
+        (Some("remove"), Ok(&[start, end])) => match inventory.remove(start, end) {
+            true => format!("Removed {start}-{end}, {} fresh IDs", inventory.total()),
+            false => format!("No range {start}-{end}"),
+        },
+        (Some("gaps"), Ok(&[])) => match inventory.gaps() {
+            gaps if gaps.is_empty() => "No gaps".to_string(),
+            gaps => format!("Spoiled gaps: {}", show(&gaps)),
+        },
+        _ => "Expected one of: fresh <id>, covers <id>, next <id>, count <low> <high>, gaps, \
+              add <start> <end>, remove <start> <end>"
+            .to_string(),
+    }
+}
+
+// Answer queries from the terminal (or a pipe) until it closes
+fn repl(inventory: &mut Inventory) {
+    let interactive = stdin().is_terminal();
+    let mut lines = stdin().lock().lines();
+    loop {
+        if interactive {
+            print!("> ");
+            stdout().flush().unwrap();
+        }
+        match lines.next() {
+            Some(Ok(line)) if line.trim() == "quit" => break,
+            Some(Ok(line)) if line.trim().is_empty() => continue,
+            Some(Ok(line)) => println!("{}", answer(inventory, &line)),
+            _ => break,
+        }
+    }
+}
+
+pub fn part1(input: &Input, _params: &Params) -> i64 {
+    let (ranges, numbers) = input;
+    let inventory = Inventory::new(ranges);
+    numbers.iter().filter(|n| inventory.is_fresh(**n)).count() as i64
+}
+
+pub fn part2(input: &Input, _params: &Params) -> i128 {
+    let (ranges, _) = input;
+    Inventory::new(ranges).total()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc2025::{
+        check,
+        generate::{self, Day05Size},
+        reference,
+    };
 
     #[test]
     fn test_part1() {
         let input = _sample_input();
-        assert_eq!(3, part1(&input));
+        assert_eq!(3, part1(&input, &Params::default()));
     }
 
     #[test]
     fn test_part2() {
         let input = _sample_input();
-        assert_eq!(14, part2(&input));
+        assert_eq!(14, part2(&input, &Params::default()));
+    }
+
+    #[test]
+    fn test_queries() {
+        let mut inventory = Inventory::new(&_sample_input().0);
+        let mut ask = |query| answer(&mut inventory, query);
+        assert_eq!("17 is fresh", ask("fresh 17"));
+        assert_eq!("8 is spoiled", ask("fresh 8"));
+        assert_eq!("17 is in 16-20, 12-18", ask("covers 17"));
+        assert_eq!("Next fresh ID after 5 is 10", ask("next 5"));
+        assert_eq!("Next fresh ID after 12 is 13", ask("next 12"));
+        assert_eq!("No fresh IDs after 20", ask("next 20"));
+        assert_eq!("5 fresh IDs in 4-12", ask("count 4 12"));
+        assert_eq!("0 fresh IDs in 15-12", ask("count 15 12"));
+        assert_eq!("Spoiled gaps: 6-9", ask("gaps"));
+        assert!(ask("fresh").starts_with("Expected"));
+        assert!(ask("count 1 x").starts_with("Expected"));
+        assert_eq!("Removed 10-14, 12 fresh IDs", ask("remove 10 14"));
+        assert_eq!("No range 10-14", ask("remove 10 14"));
+        assert_eq!("Spoiled gaps: 6-11", ask("gaps"));
+        assert_eq!("Added 6-11, 18 fresh IDs", ask("add 6 11"));
+        assert_eq!("No gaps", ask("gaps"));
+
+        // Touching and inverted ranges
+        let inventory = Inventory::new(&[(3, 5), (6, 8), (20, 10), (0, i64::MAX)]);
+        assert_eq!(i64::MAX as i128 + 1, inventory.total());
+        assert_eq!(None, inventory.next_fresh(i64::MAX));
+        assert!(inventory.coverage.covered(15, 12).is_empty());
+        assert_eq!(0, inventory.count_fresh(i64::MAX, i64::MIN));
+        assert!(Inventory::new(&[(3, 5), (6, 8)]).gaps().is_empty());
+    }
+
+    #[test]
+    fn test_updates() {
+        // Merge from scratch, to check the incremental updates against
+        let recompute = |ranges: &[(i64, i64)]| {
+            let mut sorted = ranges
+                .iter()
+                .filter(|(s, e)| s <= e)
+                .copied()
+                .collect::<Vec<_>>();
+            sorted.sort();
+            let mut merged: Vec<(i64, i64)> = Vec::new();
+            for (start, end) in sorted {
+                match merged.last_mut() {
+                    Some(last) if start <= last.1 + 1 => last.1 = last.1.max(end),
+                    _ => merged.push((start, end)),
+                }
+            }
+            merged
+        };
+        let mut rng = generate::Rng::new(5);
+        let mut inventory = Inventory::default();
+        for _ in 0..2000 {
+            let ranges = inventory.ranges().to_vec();
+            if !ranges.is_empty() && rng.chance(0.4) {
+                let (start, end) = ranges[rng.index(ranges.len())];
+                assert!(inventory.remove(start, end));
+            } else {
+                let start = rng.range(0, 100);
+                inventory.add(start, start + rng.range(-2, 15));
+            }
+            let merged = recompute(inventory.ranges());
+            assert_eq!(merged, inventory.merged());
+            assert_eq!(
+                reference::day05::part2(inventory.ranges()) as i128,
+                inventory.total()
+            );
+            for id in -1..120 {
+                assert_eq!(
+                    merged.iter().any(|&(s, e)| s <= id && id <= e),
+                    inventory.is_fresh(id)
+                );
+            }
+        }
+        assert!(!inventory.remove(200, 100));
+
+        // Ranges reaching the ends of the ID space
+        let mut inventory = Inventory::new(&[(i64::MIN, -1), (0, i64::MAX)]);
+        assert_eq!(vec![(i64::MIN, i64::MAX)], inventory.merged());
+        assert!(inventory.remove(i64::MIN, -1));
+        assert_eq!(i64::MAX as i128 + 1, inventory.total());
+        assert!(inventory.remove(0, i64::MAX));
+        assert_eq!(0, inventory.total());
+        assert!(inventory.merged().is_empty());
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let ranges = [
+            (3, 5),
+            (10, 14),
+            (16, 20),
+            (12, 18),
+            (6, 8),
+            (20, 10),
+            (12, 18),
+            (13, 14),
+        ];
+        assert_eq!(
+            vec![
+                Issue::Adjacent(0, 4),
+                Issue::Inverted(5),
+                Issue::Duplicate(6, 3),
+                Issue::Contained(7, 3),
+            ],
+            diagnose(&ranges)
+        );
+        assert_eq!(
+            "#1 3-5 is adjacent to #5 6-8
+#6 20-10 is inverted and contains no IDs
+#7 12-18 duplicates #4 12-18
+#8 13-14 is contained in #4 12-18
+4 issues, 8 ranges merge into 2 with 17 fresh IDs
+",
+            diagnostics_text(&ranges)
+        );
+        assert_eq!("3-8\n10-20\n", normalized(&ranges));
+        assert_eq!("3-5\n10-20\n", normalized(&_sample_input().0));
+        assert!(diagnose(&_sample_input().0).is_empty());
+        assert_eq!(
+            vec![Issue::Adjacent(0, 1)],
+            diagnose(&[(0, i64::MAX - 1), (i64::MAX, i64::MAX)])
+        );
+
+        // Normalizing is stable and doesn't change the answer
+        let mut rng = generate::Rng::new(43);
+        for _ in 0..100 {
+            let ranges = (0..rng.range(1, 10))
+                .map(|_| {
+                    let start = rng.range(0, 50);
+                    (start, start + rng.range(-1, 10))
+                })
+                .collect::<Vec<_>>();
+            let text = normalized(&ranges);
+            let (merged, _) = process_input(text.lines().map(|s| s.to_string()).collect()).unwrap();
+            assert_eq!(text, normalized(&merged));
+            assert!(diagnose(&merged).is_empty());
+            assert_eq!(
+                part2(&(ranges, vec![]), &Params::default()),
+                part2(&(merged, vec![]), &Params::default())
+            );
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        let lines = |data: &str| data.split('\n').map(|s| s.to_string()).collect();
+        assert!(process_input(lines("3-5\nabc")).is_err());
+        assert!(process_input(lines("3-5\n\n99999999999999999999")).is_err());
+    }
+
+    #[test]
+    fn differential() {
+        let size = Day05Size {
+            ranges: 8,
+            ids: 10,
+            max_id: 200,
+            max_width: 30,
+        };
+        check::differential(
+            300,
+            |rng| generate::day05(rng, &size).unwrap(),
+            process_input,
+            |input| {
+                (
+                    part1(input, &Params::default()),
+                    part2(input, &Params::default()),
+                )
+            },
+            |(ranges, ids)| {
+                (
+                    reference::day05::part1(ranges, ids),
+                    reference::day05::part2(ranges) as i128,
+                )
+            },
+        );
     }
 
     #[test]
     fn real_part1() {
         let input = day_input();
-        assert_eq!(761, part1(&input));
+        assert_eq!(761, part1(&input, &Params::default()));
     }
 
     #[test]
     fn real_part2() {
         let input = day_input();
-        assert_eq!(345755049374932, part2(&input));
+        assert_eq!(345755049374932, part2(&input, &Params::default()));
     }
 }
 
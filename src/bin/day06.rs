@@ -1,44 +1,330 @@
-use aoc2025::common::read_input;
+use std::{fmt, str::FromStr};
+
+use aoc2025::common::{Args, ParseError, read_input};
+
+// Parsed puzzle input
+pub type Input = (Vec<Operator>, Vec<Vec<char>>);
 
 const DAY: u32 = 6;
 
+// Operation applied to all the numbers of a problem
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Max,
+    Min,
+    Power,
+    Remainder,
+}
+
+impl FromStr for Operator {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Operator, String> {
+        match s {
+            "+" => Ok(Operator::Add),
+            "-" => Ok(Operator::Subtract),
+            "*" => Ok(Operator::Multiply),
+            "/" => Ok(Operator::Divide),
+            "max" => Ok(Operator::Max),
+            "min" => Ok(Operator::Min),
+            "^" => Ok(Operator::Power),
+            "%" => Ok(Operator::Remainder),
+            _ => Err(format!("Unknown symbol {s}")),
+        }
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let symbol = match self {
+            Operator::Add => "+",
+            Operator::Subtract => "-",
+            Operator::Multiply => "*",
+            Operator::Divide => "/",
+            Operator::Max => "max",
+            Operator::Min => "min",
+            Operator::Power => "^",
+            Operator::Remainder => "%",
+        };
+        write!(f, "{symbol}")
+    }
+}
+
+impl Operator {
+    // None if the result is undefined or doesn't fit in an i64
+    pub fn apply(self, a: i64, b: i64) -> Option<i64> {
+        match self {
+            Operator::Add => a.checked_add(b),
+            Operator::Subtract => a.checked_sub(b),
+            Operator::Multiply => a.checked_mul(b),
+            Operator::Divide => a.checked_div(b),
+            Operator::Max => Some(a.max(b)),
+            Operator::Min => Some(a.min(b)),
+            Operator::Power => a.checked_pow(u32::try_from(b).ok()?),
+            Operator::Remainder => a.checked_rem(b),
+        }
+    }
+}
+
+// Which end of a problem to start from, which matters for operators like `-`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Order {
+    // ((a - b) - c)
+    LeftToRight,
+    // (a - (b - c))
+    RightToLeft,
+}
+
+impl FromStr for Order {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Order, String> {
+        match s {
+            "ltr" => Ok(Order::LeftToRight),
+            "rtl" => Ok(Order::RightToLeft),
+            _ => Err(format!("Expected ltr or rtl, got {s}")),
+        }
+    }
+}
+
+// How to read the numbers out of a problem block, written as `rows` or
+// `columns` optionally followed by `,reverse` to take the numbers from the
+// bottom or right, and `,lsd` to read each number least significant digit
+// first
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Reading {
+    columns: bool,
+    reverse: bool,
+    lsd: bool,
+}
+
+impl Reading {
+    pub const ROWS: Reading = Reading {
+        columns: false,
+        reverse: false,
+        lsd: false,
+    };
+    pub const COLUMNS: Reading = Reading {
+        columns: true,
+        ..Reading::ROWS
+    };
+
+    // Every supported reading
+    pub fn all() -> Vec<Reading> {
+        let mut readings = Vec::new();
+        for columns in [false, true] {
+            for reverse in [false, true] {
+                for lsd in [false, true] {
+                    readings.push(Reading {
+                        columns,
+                        reverse,
+                        lsd,
+                    });
+                }
+            }
+        }
+        readings
+    }
+}
+
+impl FromStr for Reading {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Reading, String> {
+        let mut parts = s.split(',');
+        let mut reading = match parts.next() {
+            Some("rows") => Reading::ROWS,
+            Some("columns") => Reading::COLUMNS,
+            _ => return Err(format!("Expected rows or columns, got {s}")),
+        };
+        for part in parts {
+            match part {
+                "reverse" => reading.reverse = true,
+                "lsd" => reading.lsd = true,
+                _ => return Err(format!("Expected reverse or lsd, got {part}")),
+            }
+        }
+        Ok(reading)
+    }
+}
+
+impl fmt::Display for Reading {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", if self.columns { "columns" } else { "rows" })?;
+        if self.reverse {
+            write!(f, ",reverse")?;
+        }
+        if self.lsd {
+            write!(f, ",lsd")?;
+        }
+        Ok(())
+    }
+}
+
+// Puzzle parameters, overridable with `--param key=value`
+#[derive(Clone, Copy, Debug)]
+pub struct Params {
+    order: Order,
+    reading1: Reading,
+    reading2: Reading,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            order: Order::LeftToRight,
+            reading1: Reading::ROWS,
+            reading2: Reading::COLUMNS,
+        }
+    }
+}
+
+impl Params {
+    fn from_args(args: &Args) -> Params {
+        args.expect_params(&["order", "reading1", "reading2"]);
+        let default = Params::default();
+        Params {
+            order: args.param("order", default.order),
+            reading1: args.param("reading1", default.reading1),
+            reading2: args.param("reading2", default.reading2),
+        }
+    }
+}
+
+// Problem that couldn't be evaluated, with the column where it starts
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EvalError {
+    pub problem: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Problem {} at column {}: {}",
+            self.problem + 1,
+            self.column,
+            self.message
+        )
+    }
+}
+
 pub fn main() {
+    let args = Args::from_env();
+    args.expect_options(&["debug"]);
+    let params = Params::from_args(&args);
     let input = day_input();
-    let part1_result = part1(&input);
-    println!("Day {DAY}, part 1: {part1_result}");
-    let part2_result = part2(&input);
-    println!("Day {DAY}, part 2: {part2_result}");
+    if args.flag("debug") {
+        for (part, reading) in [(1, params.reading1), (2, params.reading2)] {
+            println!("Part {part}, reading {reading}\n");
+            print!("{}", debug_view(&input, reading, params.order));
+        }
+    }
+    match part1(&input, &params) {
+        Ok(result) => println!("Day {DAY}, part 1: {result}"),
+        Err(e) => println!("Day {DAY}, part 1 failed: {e}"),
+    }
+    match part2(&input, &params) {
+        Ok(result) => println!("Day {DAY}, part 2: {result}"),
+        Err(e) => println!("Day {DAY}, part 2 failed: {e}"),
+    }
 }
 
 // Parse input
-fn process_input(input: Vec<String>) -> (Vec<String>, Vec<Vec<i64>>, Vec<Vec<char>>) {
-    let symbols: Vec<String> = input
-        .last()
-        .unwrap()
+pub fn process_input(input: Vec<String>) -> Result<Input, ParseError> {
+    if input.len() < 2 {
+        return Err(ParseError::at(
+            input.len(),
+            "Expected rows of numbers followed by a row of operators",
+        ));
+    }
+    let last = input.len() - 1;
+    let symbols: Vec<Operator> = input[last]
         .split(" ")
         .filter(|s| !s.is_empty())
-        .map(|s| s.to_owned())
-        .collect();
-    let data = input
+        .map(|s| s.parse().map_err(|e: String| ParseError::at(last, e)))
+        .collect::<Result<_, _>>()?;
+    if symbols.is_empty() {
+        return Err(ParseError::at(last, "Expected operators"));
+    }
+    for (i, row) in input.iter().take(last).enumerate() {
+        let mut count = 0;
+        for v in row.split(" ").filter(|s| !s.is_empty()) {
+            v.parse::<i64>()
+                .map_err(|e| ParseError::at(i, format!("Invalid number {v}: {e}")))?;
+            count += 1;
+        }
+        if count != symbols.len() {
+            return Err(ParseError::at(
+                i,
+                format!("Expected {} numbers, found {count}", symbols.len()),
+            ));
+        }
+    }
+
+    // Pad the rows to the same width, and check that the problems are
+    // separated by single blank columns
+    let width = input
+        .iter()
+        .take(last)
+        .map(|row| row.chars().count())
+        .max()
+        .unwrap();
+    let grid = input
         .iter()
-        .take(input.len() - 1)
+        .take(last)
         .map(|row| {
-            row.split(" ")
-                .filter(|s| !s.is_empty())
-                .map(|v| v.parse::<i64>().unwrap())
-                .collect()
+            let mut chars = row.chars().collect::<Vec<char>>();
+            chars.resize(width, ' ');
+            chars
         })
-        .collect::<Vec<Vec<i64>>>();
-    let data2 = input
-        .iter()
-        .take(input.len() - 1)
-        .map(|row| row.chars().collect::<Vec<char>>())
         .collect::<Vec<_>>();
-    (symbols, data, data2)
+    let mut blank = vec![false];
+    for x in 0..width {
+        let column: String = grid.iter().map(|row| row[x]).collect();
+        if column.trim().is_empty() {
+            blank.push(true);
+        } else if column.trim().parse::<i64>().is_ok() {
+            blank.push(false);
+        } else {
+            // Point at the first character after the column's leading digits
+            let chars: Vec<char> = column.chars().collect();
+            let start = chars.iter().position(|c| *c != ' ').unwrap();
+            let end = start
+                + chars[start..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_digit())
+                    .count();
+            let y = chars[end..]
+                .iter()
+                .position(|c| *c != ' ')
+                .map_or(start, |y| end + y);
+            return Err(ParseError::at(y, format!("Column {x} is not a number")));
+        }
+    }
+    blank.push(true);
+    let problems = blank.windows(2).filter(|w| !w[0] && w[1]).count();
+    let separators = blank.iter().filter(|b| **b).count() - 1;
+    if problems != symbols.len() || separators + 1 != symbols.len() {
+        return Err(ParseError::at(
+            0,
+            format!(
+                "Expected {} problems separated by single blank columns",
+                symbols.len()
+            ),
+        ));
+    }
+    Ok((symbols, grid))
 }
 
 // Parse sample input
-fn _sample_input() -> (Vec<String>, Vec<Vec<i64>>, Vec<Vec<char>>) {
+fn _sample_input() -> Input {
     let data = "123 328  51 64 
  45 64  387 23 
   6 98  215 314
@@ -46,85 +332,311 @@ fn _sample_input() -> (Vec<String>, Vec<Vec<i64>>, Vec<Vec<char>>) {
         .split('\n')
         .map(|s| s.to_string())
         .collect();
-    process_input(data)
+    process_input(data).unwrap()
 }
 
 // Parse day's input
-fn day_input() -> (Vec<String>, Vec<Vec<i64>>, Vec<Vec<char>>) {
-    process_input(read_input(DAY))
-}
-
-fn part1(input: &(Vec<String>, Vec<Vec<i64>>, Vec<Vec<char>>)) -> i64 {
-    let (symbols, data, _) = input;
-    let mut results: Vec<i64> = data.first().unwrap().clone();
-    for row in data.iter().skip(1) {
-        for (i, symbol) in symbols.iter().enumerate() {
-            match symbol.as_str() {
-                "*" => results[i] *= row[i],
-                "+" => results[i] += row[i],
-                _ => panic!("Unknown symbol {}", symbol),
-            }
-        }
+fn day_input() -> Input {
+    process_input(read_input(DAY)).unwrap_or_else(|e| panic!("Invalid input: {e}"))
+}
+
+// Character grid of each problem block, with the column where it starts
+fn blocks(grid: &[Vec<char>]) -> Vec<(usize, Vec<Vec<char>>)> {
+    let blank = |x: usize| grid.iter().all(|row| row[x] == ' ');
+    let width = grid[0].len();
+    (0..width)
+        .filter(|&x| !blank(x) && (x == 0 || blank(x - 1)))
+        .map(|start| {
+            let end = (start..width).find(|&x| blank(x)).unwrap_or(width);
+            let block = grid.iter().map(|row| row[start..end].to_vec()).collect();
+            (start, block)
+        })
+        .collect()
+}
+
+// Numbers of a problem block under the given reading
+fn read_block(block: &[Vec<char>], reading: Reading) -> Result<Vec<i64>, String> {
+    let mut lines: Vec<Vec<char>> = if reading.columns {
+        (0..block[0].len())
+            .map(|x| block.iter().map(|row| row[x]).collect())
+            .collect()
+    } else {
+        block.to_vec()
+    };
+    if reading.reverse {
+        lines.reverse();
     }
-    results.iter().sum()
+    lines
+        .iter()
+        .map(|line| {
+            let mut digits = line
+                .iter()
+                .filter(|c| c.is_ascii_digit())
+                .collect::<String>();
+            if reading.lsd {
+                digits = digits.chars().rev().collect();
+            }
+            digits
+                .parse::<i64>()
+                .map_err(|e| format!("Can't read {:?} as a number: {e}", digits))
+        })
+        .collect()
+}
+
+// Numbers of every problem under the given reading
+pub fn read_problems(input: &Input, reading: Reading) -> Result<Vec<Vec<i64>>, EvalError> {
+    blocks(&input.1)
+        .iter()
+        .enumerate()
+        .map(|(i, (column, block))| {
+            read_block(block, reading).map_err(|message| EvalError {
+                problem: i,
+                column: *column,
+                message,
+            })
+        })
+        .collect()
 }
 
-fn part2(input: &(Vec<String>, Vec<Vec<i64>>, Vec<Vec<char>>)) -> i64 {
-    let (symbols, _, data) = input;
-    let y_len = data.len();
-    let x_len = data.first().unwrap().len();
-    let mut numbers: Vec<Vec<i64>> = vec![Vec::new(); symbols.len()];
-    let mut i = 0;
-    for x in 0..x_len {
-        let mut current = Vec::new();
-        for y in 0..y_len {
-            current.push(data[y][x]);
+// Apply the operator to all the numbers, starting from either end
+pub fn evaluate(operator: Operator, numbers: &[i64], order: Order) -> Result<i64, String> {
+    let step = |a: i64, b: i64| {
+        operator
+            .apply(a, b)
+            .ok_or_else(|| format!("{a} {operator} {b} is undefined or overflows"))
+    };
+    match (order, numbers) {
+        (_, []) => Err("No numbers".to_string()),
+        (Order::LeftToRight, [first, rest @ ..]) => {
+            rest.iter().try_fold(*first, |acc, &n| step(acc, n))
         }
-        let joined: String = current.iter().collect();
-        let number = joined.trim().parse::<i64>().ok();
-        if let Some(n) = number {
-            numbers[i].push(n);
-        } else {
-            i += 1;
+        (Order::RightToLeft, [rest @ .., last]) => {
+            rest.iter().rev().try_fold(*last, |acc, &n| step(n, acc))
         }
     }
+}
 
+// Sum of the answers to all the problems
+pub fn solve(input: &Input, reading: Reading, order: Order) -> Result<i64, EvalError> {
+    let (symbols, grid) = input;
+    let problems = read_problems(input, reading)?;
     symbols
         .iter()
+        .zip(problems)
+        .zip(blocks(grid))
         .enumerate()
-        .map(|(i, symbol)| match symbol.as_str() {
-            "*" => numbers[i].iter().copied().product::<i64>(),
-            "+" => numbers[i].iter().copied().sum::<i64>(),
-            _ => panic!("Unknown symbol {}", symbol),
+        .try_fold(0i64, |total, (i, ((&operator, numbers), (column, _)))| {
+            let error = |message| EvalError {
+                problem: i,
+                column,
+                message,
+            };
+            let answer = evaluate(operator, &numbers, order).map_err(error)?;
+            total
+                .checked_add(answer)
+                .ok_or_else(|| error("The total doesn't fit in 64 bits".to_string()))
         })
-        .sum()
+}
+
+// Every problem block with the numbers read from it and its answer
+pub fn debug_view(input: &Input, reading: Reading, order: Order) -> String {
+    let (symbols, grid) = input;
+    let mut text = String::new();
+    for (i, (column, block)) in blocks(grid).iter().enumerate() {
+        text.push_str(&format!(
+            "Problem {} at column {column}: {}\n",
+            i + 1,
+            symbols[i]
+        ));
+        for row in block {
+            text.push_str(&format!("  {}\n", row.iter().collect::<String>()));
+        }
+        let result = read_block(block, reading).and_then(|numbers| {
+            let shown = numbers.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+            text.push_str(&format!("  Numbers: {}\n", shown.join(", ")));
+            evaluate(symbols[i], &numbers, order)
+        });
+        match result {
+            Ok(answer) => text.push_str(&format!("  Answer: {answer}\n\n")),
+            Err(message) => text.push_str(&format!("  Error: {message}\n\n")),
+        }
+    }
+    text
+}
+
+// Each row is a number by default
+pub fn part1(input: &Input, params: &Params) -> Result<i64, EvalError> {
+    solve(input, params.reading1, params.order)
+}
+
+// Each column is a number by default, read top to bottom
+pub fn part2(input: &Input, params: &Params) -> Result<i64, EvalError> {
+    solve(input, params.reading2, params.order)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc2025::{
+        check,
+        generate::{self, Day06Size},
+        reference,
+    };
 
     #[test]
     fn test_part1() {
         let input = _sample_input();
-        assert_eq!(4277556, part1(&input));
+        assert_eq!(Ok(4277556), part1(&input, &Params::default()));
     }
 
     #[test]
     fn test_part2() {
         let input = _sample_input();
-        assert_eq!(3263827, part2(&input));
+        assert_eq!(Ok(3263827), part2(&input, &Params::default()));
+    }
+
+    #[test]
+    fn test_operators() {
+        let lines = |data: &str| data.split('\n').map(|s| s.to_string()).collect();
+        let ltr = Params::default();
+        let rtl = Params {
+            order: Order::RightToLeft,
+            ..Params::default()
+        };
+        let input = process_input(lines(
+            "100 2 3   7 2 9 17\n 20 3 2  11 5 4  5\n  3 2 2 100 2 7  3\n-   ^ / max min % %",
+        ))
+        .unwrap();
+        // 77 + 64 + 0 + 100 + 2 + 1 + 2
+        assert_eq!(Ok(246), part1(&input, &ltr));
+        // 83 + 512 + 3 + 100 + 2 + 1 + 1
+        assert_eq!(Ok(702), part1(&input, &rtl));
+        assert_eq!(Some(8), Operator::Power.apply(2, 3));
+        assert_eq!(None, Operator::Power.apply(2, -1));
+        assert_eq!(None, Operator::Divide.apply(i64::MIN, -1));
+        assert_eq!(
+            Err("No numbers".to_string()),
+            evaluate(Operator::Add, &[], Order::LeftToRight)
+        );
+
+        // Errors name the problem and the column it starts at
+        let input = process_input(lines("12 5 4\n 3 0 2\n+  / *")).unwrap();
+        assert_eq!(
+            "Problem 2 at column 3: 5 / 0 is undefined or overflows",
+            part1(&input, &ltr).unwrap_err().to_string()
+        );
+        let input = process_input(lines("4000000000\n4000000000\n*")).unwrap();
+        assert_eq!(
+            Err(EvalError {
+                problem: 0,
+                column: 0,
+                message: "4000000000 * 4000000000 is undefined or overflows".to_string()
+            }),
+            part1(&input, &ltr)
+        );
+        let input = process_input(lines("9223372036854775807 1\n+                   +")).unwrap();
+        assert_eq!(
+            "Problem 2 at column 20: The total doesn't fit in 64 bits",
+            part1(&input, &ltr).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn test_readings() {
+        let input = _sample_input();
+        let total = |reading: &str| solve(&input, reading.parse().unwrap(), Order::LeftToRight);
+        assert_eq!(Ok(4277556), total("rows"));
+        assert_eq!(Ok(3263827), total("columns"));
+        assert_eq!(Ok(6118893), total("rows,lsd"));
+        assert_eq!(Ok(2459308), total("columns,lsd"));
+        for reading in Reading::all() {
+            assert_eq!(Ok(reading), reading.to_string().parse());
+            let reversed = Reading {
+                reverse: !reading.reverse,
+                ..reading
+            };
+            assert_eq!(total(&reading.to_string()), total(&reversed.to_string()));
+        }
+        assert!("diagonal".parse::<Reading>().is_err());
+        assert!("rows,up".parse::<Reading>().is_err());
+
+        // Subtraction shows which end the numbers are taken from
+        let lines = |data: &str| data.split('\n').map(|s| s.to_string()).collect();
+        let input = process_input(lines("12\n34\n- ")).unwrap();
+        let total = |reading: &str| solve(&input, reading.parse().unwrap(), Order::LeftToRight);
+        assert_eq!(Ok(-22), total("rows"));
+        assert_eq!(Ok(22), total("rows,reverse"));
+        assert_eq!(Ok(-22), total("rows,lsd"));
+        assert_eq!(Ok(-11), total("columns"));
+        assert_eq!(Ok(11), total("columns,reverse"));
+        assert_eq!(Ok(-11), total("columns,lsd"));
+        assert_eq!(
+            "Problem 1 at column 0: -
+  12
+  34
+  Numbers: 24, 13
+  Answer: 11
+
+",
+            debug_view(
+                &input,
+                "columns,reverse".parse().unwrap(),
+                Order::LeftToRight
+            )
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        let lines = |data: &str| data.split('\n').map(|s| s.to_string()).collect();
+        assert!(process_input(lines("1 2\n*")).is_err());
+        assert!(process_input(lines("1\n?")).is_err());
+        assert!(process_input(lines("1 2 *")).is_err());
+        assert!(process_input(lines("12 3\n4 56\n*  +")).is_err());
+        let error = process_input(lines("1 2\n 3 4\n5 6\n* +")).unwrap_err();
+        assert_eq!(3, error.line);
+        assert_eq!("Column 0 is not a number", error.message);
+        let error = process_input(lines("1 2\n-3 4\n* +")).unwrap_err();
+        assert_eq!(2, error.line);
+    }
+
+    #[test]
+    fn differential() {
+        let size = Day06Size {
+            problems: 6,
+            rows: 4,
+            max_digits: 4,
+        };
+        check::differential(
+            300,
+            |rng| generate::day06(rng, &size).unwrap(),
+            process_input,
+            |input| {
+                let params = Params::default();
+                (
+                    part1(input, &params).unwrap(),
+                    part2(input, &params).unwrap(),
+                )
+            },
+            |(symbols, grid)| {
+                let symbols = symbols.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+                (
+                    reference::day06::part1(&symbols, grid),
+                    reference::day06::part2(&symbols, grid),
+                )
+            },
+        );
     }
 
     #[test]
     fn real_part1() {
         let input = day_input();
-        assert_eq!(5227286044585, part1(&input));
+        assert_eq!(Ok(5227286044585), part1(&input, &Params::default()));
     }
 
     #[test]
     fn real_part2() {
         let input = day_input();
-        assert_eq!(10227753257799, part2(&input));
+        assert_eq!(Ok(10227753257799), part2(&input, &Params::default()));
     }
 }
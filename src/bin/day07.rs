@@ -1,20 +1,131 @@
-use std::collections::{HashMap, HashSet};
+use std::{collections::VecDeque, fmt};
 
-use aoc2025::common::read_input;
+use aoc2025::{
+    common::{Args, ParseError, read_input},
+    render::{self, Image},
+};
 
 const DAY: u32 = 7;
 
+// Puzzle parameters, overridable with `--param key=value`
+#[derive(Clone, Debug)]
+pub struct Params {
+    // Where a splitter sends beams, as offsets across the direction of travel
+    offsets: Vec<i64>,
+    // Beams leaving one side come back in on the other
+    wrap: bool,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            offsets: vec![-1, 1],
+            wrap: false,
+        }
+    }
+}
+
+impl Params {
+    fn from_args(args: &Args) -> Params {
+        args.expect_params(&["offsets", "wrap"]);
+        let default = Params::default();
+        // Offsets are given as a comma separated list, e.g. `offsets=-2,0,2`
+        let offsets = match args.param("offsets", String::new()).as_str() {
+            "" => default.offsets,
+            list => list
+                .split(',')
+                .map(|o| {
+                    o.parse()
+                        .unwrap_or_else(|e| panic!("Invalid offset {o}: {e:?}"))
+                })
+                .collect(),
+        };
+        Params {
+            offsets,
+            wrap: args.param("wrap", default.wrap),
+        }
+    }
+}
+
+// Why the timelines can't be counted
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BeamError {
+    // Beams come back to the same cell going the same way, forever
+    Cycle(usize, usize),
+    // More timelines than fit in a u128
+    Overflow,
+}
+
+impl fmt::Display for BeamError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BeamError::Cycle(x, y) => write!(f, "Beams loop forever through ({x}, {y})"),
+            BeamError::Overflow => write!(f, "Too many timelines to count"),
+        }
+    }
+}
+
+// Number of splitters the beams reach, and number of timelines leaving
+// through the bottom
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Beams {
+    pub splits: i64,
+    pub timelines: Result<u128, BeamError>,
+}
+
 pub fn main() {
+    let args = Args::from_env();
+    args.expect_options(&["beams", "heatmap", "scale"]);
+    let params = Params::from_args(&args);
     let input = day_input();
-    let part1_result = part1(&input);
+    if args.flag("beams") {
+        print!("{}", draw_beams(&input, &params));
+    }
+    if args.flag("heatmap") {
+        match heat(&input, &params) {
+            Ok(heat) => match args.option("heatmap") {
+                Some(path) => {
+                    // Pixels per cell
+                    let scale: usize = args.value("scale").map_or(4, |s| {
+                        s.parse()
+                            .unwrap_or_else(|e| panic!("Invalid scale {s}: {e}"))
+                    });
+                    render_heat(&input, &heat)
+                        .scaled(scale)
+                        .write_ppm(path)
+                        .unwrap_or_else(|e| panic!("Could not write {path}: {e}"));
+                }
+                None => print!("{}", heatmap_text(&input, &heat)),
+            },
+            Err(e) => println!("No heatmap: {e}"),
+        }
+    }
+    let part1_result = part1(&input, &params);
     println!("Day {DAY}, part 1: {part1_result}");
-    let part2_result = part2(&input);
-    println!("Day {DAY}, part 2: {part2_result}");
+    match part2(&input, &params) {
+        Ok(result) => println!("Day {DAY}, part 2: {result}"),
+        Err(e) => println!("Day {DAY}, part 2 failed: {e}"),
+    }
 }
 
 // Parse input
-fn process_input(input: Vec<String>) -> Vec<Vec<char>> {
-    input.iter().map(|row| row.chars().collect()).collect()
+pub fn process_input(input: Vec<String>) -> Result<Vec<Vec<char>>, ParseError> {
+    let grid: Vec<Vec<char>> = input.iter().map(|row| row.chars().collect()).collect();
+    for (y, row) in grid.iter().enumerate() {
+        if row.len() != grid[0].len() {
+            return Err(ParseError::at(y, "Rows have different lengths"));
+        }
+        if let Some(c) = row.iter().find(|c| !".S^/\\#".contains(**c)) {
+            return Err(ParseError::at(y, format!("Unknown symbol {c:?}")));
+        }
+    }
+    if grid.is_empty() || grid[0].is_empty() {
+        return Err(ParseError::at(0, "Empty manifold"));
+    }
+    if !grid.iter().flatten().any(|&c| c == 'S') {
+        return Err(ParseError::at(0, "Expected at least one S"));
+    }
+    Ok(grid)
 }
 
 // Parse sample input
@@ -38,144 +149,637 @@ fn _sample_input() -> Vec<Vec<char>> {
         .split('\n')
         .map(|s| s.to_string())
         .collect();
-    process_input(data)
+    process_input(data).unwrap()
 }
 
 // Parse day's input
 fn day_input() -> Vec<Vec<char>> {
-    process_input(read_input(DAY))
+    process_input(read_input(DAY)).unwrap_or_else(|e| panic!("Invalid input: {e}"))
 }
 
-fn part1(input: &Vec<Vec<char>>) -> i64 {
-    let start = input
-        .first()
-        .unwrap()
-        .iter()
-        .position(|&c| c == 'S')
-        .unwrap();
-    let mut processed = HashSet::new();
-    let mut remaining = vec![(start, 0 as usize)];
-    let mut splits = HashSet::new();
-    let x_len = input[0].len();
-    while !remaining.is_empty() {
-        let pos = remaining.pop().unwrap();
-        if processed.contains(&pos) {
-            continue;
-        }
-        processed.insert(pos);
-        let mut next = (pos.0, pos.1 + 1);
-        while next.1 < input.len() && input[next.1][next.0] == '.' {
-            // Continue beam
-            next.1 += 1;
-        }
-        if next.1 < input.len() {
-            // Reached a splitter
-            splits.insert(next);
-            if next.0 > 0 {
-                remaining.push((next.0 - 1, next.1));
-            }
-            if next.0 + 1 < x_len {
-                remaining.push((next.0 + 1, next.1));
+// Step in x and y
+type Direction = (i64, i64);
+
+const DOWN: Direction = (0, 1);
+
+const DIRECTIONS: [Direction; 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+
+// Where a beam ends up after a move
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Move {
+    Cell(usize, usize),
+    Bottom,
+    Lost,
+}
+
+// The manifold together with the rules beams follow in it
+struct Manifold<'a> {
+    grid: &'a [Vec<char>],
+    width: usize,
+    height: usize,
+    params: &'a Params,
+}
+
+impl Manifold<'_> {
+    fn new<'a>(grid: &'a [Vec<char>], params: &'a Params) -> Manifold<'a> {
+        Manifold {
+            grid,
+            width: grid[0].len(),
+            height: grid.len(),
+            params,
+        }
+    }
+
+    fn sources(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.height)
+            .flat_map(move |y| (0..self.width).map(move |x| (x, y)))
+            .filter(|&(x, y)| self.grid[y][x] == 'S')
+    }
+
+    // Beams coming out of the sources
+    fn starts(&self) -> Vec<(Move, Direction)> {
+        self.sources()
+            .map(|(x, y)| (self.moved(x, y, DOWN), DOWN))
+            .collect()
+    }
+
+    // Index of a beam in a cell moving in a direction
+    fn state(&self, x: usize, y: usize, dir: Direction) -> usize {
+        (y * self.width + x) * 4 + DIRECTIONS.iter().position(|&d| d == dir).unwrap()
+    }
+
+    fn cell(&self, state: usize) -> (usize, usize, Direction) {
+        let cell = state / 4;
+        (cell % self.width, cell / self.width, DIRECTIONS[state % 4])
+    }
+
+    // States some beam gets to
+    fn reached(&self) -> Vec<bool> {
+        let mut seen = vec![false; self.width * self.height * 4];
+        let mut queue = self.starts().into_iter().collect::<VecDeque<_>>();
+        while let Some((m, dir)) = queue.pop_front() {
+            let Move::Cell(x, y) = m else {
+                continue;
+            };
+            if std::mem::replace(&mut seen[self.state(x, y, dir)], true) {
+                continue;
             }
+            queue.extend(self.next(x, y, dir));
+        }
+        seen
+    }
+
+    fn has_mirrors(&self) -> bool {
+        self.grid.iter().flatten().any(|&c| c == '/' || c == '\\')
+    }
+
+    fn moved(&self, x: usize, y: usize, (dx, dy): Direction) -> Move {
+        let (width, height) = (self.width as i64, self.height as i64);
+        let mut nx = x as i64 + dx;
+        let ny = y as i64 + dy;
+        if self.params.wrap {
+            nx = nx.rem_euclid(width);
+        }
+        if ny >= height {
+            Move::Bottom
+        } else if nx < 0 || nx >= width || ny < 0 {
+            Move::Lost
+        } else {
+            Move::Cell(nx as usize, ny as usize)
+        }
+    }
+
+    // Where a splitter sends a beam across its direction of travel
+    fn lateral(&self, x: usize, y: usize, (dx, dy): Direction) -> Vec<Move> {
+        self.params
+            .offsets
+            .iter()
+            .map(|&o| self.moved(x, y, (o * dy.abs(), o * dx.abs())))
+            .collect()
+    }
+
+    // Where a beam in the cell moving in the direction goes next, and which
+    // way it is moving then. Splitters move the beam across its direction of
+    // travel first, ignoring the tile it lands on.
+    fn next(&self, x: usize, y: usize, (dx, dy): Direction) -> Vec<(Move, Direction)> {
+        match self.grid[y][x] {
+            '/' => vec![(self.moved(x, y, (-dy, -dx)), (-dy, -dx))],
+            '\\' => vec![(self.moved(x, y, (dy, dx)), (dy, dx))],
+            '#' => vec![],
+            '^' => self
+                .lateral(x, y, (dx, dy))
+                .into_iter()
+                .map(|m| match m {
+                    Move::Cell(lx, ly) => (self.moved(lx, ly, (dx, dy)), (dx, dy)),
+                    other => (other, (dx, dy)),
+                })
+                .collect(),
+            _ => vec![(self.moved(x, y, (dx, dy)), (dx, dy))],
         }
     }
-    splits.len() as i64
 }
 
-fn part2(input: &Vec<Vec<char>>) -> i64 {
-    let start = input
-        .first()
-        .unwrap()
-        .iter()
-        .position(|&c| c == 'S')
-        .unwrap();
-    let mut processed = HashSet::new();
-    let mut remaining = vec![((start, 0 as usize), (start, 0 as usize))];
-    let mut edges = HashSet::new();
-    let mut ends = HashSet::new();
-    let x_len = input[0].len();
-    while !remaining.is_empty() {
-        let (pos, prev) = remaining.pop().unwrap();
-        if processed.contains(&(pos, prev)) {
-            continue;
-        }
-        processed.insert((pos, prev));
-        let mut next = (pos.0, pos.1 + 1);
-        while next.1 < input.len() && input[next.1][next.0] == '.' {
-            // Continue beam
-            next.1 += 1;
-        }
-        edges.insert((prev, next));
-        if next.1 < input.len() {
-            // Reached a splitter
-            if next.0 > 0 {
-                remaining.push(((next.0 - 1, next.1), next));
+// Move every beam down one row at a time, keeping the number of timelines in
+// each column. Only works without mirrors, which are the only way for beams to
+// turn sideways or up.
+pub fn sweep(input: &[Vec<char>], params: &Params) -> Beams {
+    let manifold = Manifold::new(input, params);
+    let width = manifold.width;
+    let mut timelines = vec![0u128; width];
+    let mut splits = 0;
+    let mut overflow = false;
+    for (y, row) in input.iter().enumerate() {
+        let mut next = vec![0u128; width];
+        let mut add = |x: usize, count: u128| {
+            let (sum, overflowed) = next[x].overflowing_add(count);
+            // Saturate, so splitters reached are still counted
+            next[x] = if overflowed { u128::MAX } else { sum };
+            overflow |= overflowed;
+        };
+        for (x, &count) in timelines.iter().enumerate() {
+            if count == 0 {
+                continue;
             }
-            if next.0 + 1 < x_len {
-                remaining.push(((next.0 + 1, next.1), next));
+            if row[x] == '^' {
+                // Reached a splitter
+                splits += 1;
+            }
+            // Beams leaving through the bottom are only counted, so their
+            // column doesn't matter
+            for (m, _) in manifold.next(x, y, DOWN) {
+                match m {
+                    Move::Cell(nx, _) => add(nx, count),
+                    Move::Bottom => add(x, count),
+                    Move::Lost => {}
+                }
+            }
+        }
+        for (x, c) in row.iter().enumerate() {
+            if *c == 'S' {
+                add(x, 1);
             }
-        } else {
-            ends.insert(next);
         }
+        timelines = next;
+    }
+    let total = timelines
+        .iter()
+        .try_fold(0u128, |total, &count| total.checked_add(count));
+    Beams {
+        splits,
+        timelines: total.filter(|_| !overflow).ok_or(BeamError::Overflow),
     }
+}
+
+// Follow beams in every direction through the manifold, as a graph of cells
+// and directions
+pub fn trace(input: &[Vec<char>], params: &Params) -> Beams {
+    let manifold = Manifold::new(input, params);
+    let index = |x: usize, y: usize, dir: Direction| manifold.state(x, y, dir);
+    let starts = manifold.starts();
+
+    // Splitters reached by any beam
+    let seen = manifold.reached();
+    let splits = (0..manifold.width * manifold.height)
+        .filter(|&c| input[c / manifold.width][c % manifold.width] == '^')
+        .filter(|&c| seen[c * 4..c * 4 + 4].contains(&true))
+        .count() as i64;
+    // Timelines from each state, with states still being counted on the stack
+    let mut counts: Vec<Option<u128>> = vec![None; seen.len()];
+    let mut active = vec![false; seen.len()];
+    let mut count = |m: Move, dir: Direction| -> Result<u128, BeamError> {
+        let Move::Cell(x, y) = m else {
+            return Ok((m == Move::Bottom) as u128);
+        };
+        let mut stack = vec![(x, y, dir, false)];
+        while let Some((x, y, dir, expanded)) = stack.pop() {
+            let i = index(x, y, dir);
+            if expanded {
+                let mut total = 0u128;
+                for (m, d) in manifold.next(x, y, dir) {
+                    let n = match m {
+                        Move::Cell(nx, ny) => counts[index(nx, ny, d)].unwrap(),
+                        Move::Bottom => 1,
+                        Move::Lost => 0,
+                    };
+                    total = total.checked_add(n).ok_or(BeamError::Overflow)?;
+                }
+                counts[i] = Some(total);
+                active[i] = false;
+                continue;
+            }
+            if counts[i].is_some() {
+                continue;
+            }
+            active[i] = true;
+            stack.push((x, y, dir, true));
+            for (m, d) in manifold.next(x, y, dir) {
+                if let Move::Cell(nx, ny) = m {
+                    if active[index(nx, ny, d)] {
+                        return Err(BeamError::Cycle(nx, ny));
+                    }
+                    stack.push((nx, ny, d, false));
+                }
+            }
+        }
+        Ok(counts[index(x, y, dir)].unwrap())
+    };
+    let timelines = starts.iter().try_fold(0u128, |total, &(m, dir)| {
+        total.checked_add(count(m, dir)?).ok_or(BeamError::Overflow)
+    });
+    Beams { splits, timelines }
+}
 
-    let mut steps = HashMap::new();
+// Sweep down the manifold unless mirrors can send beams elsewhere
+pub fn beams(input: &[Vec<char>], params: &Params) -> Beams {
+    if Manifold::new(input, params).has_mirrors() {
+        trace(input, params)
+    } else {
+        sweep(input, params)
+    }
+}
 
-    fn recursive(
-        current: (usize, usize),
-        ends: &HashSet<(usize, usize)>,
-        edges: &HashSet<((usize, usize), (usize, usize))>,
-        steps: &mut HashMap<(usize, usize), i64>,
-    ) -> i64 {
-        if ends.contains(&current) {
-            return 1;
+// The manifold with beams drawn in, as `|` where they move up or down, `-`
+// where they move sideways and `+` where they do both
+pub fn draw_beams(input: &[Vec<char>], params: &Params) -> String {
+    let manifold = Manifold::new(input, params);
+    let seen = manifold.reached();
+    let mut vertical = vec![false; manifold.width * manifold.height];
+    let mut horizontal = vertical.clone();
+    let mut mark = |x: usize, y: usize, (dx, _): Direction| {
+        let marks = if dx == 0 {
+            &mut vertical
+        } else {
+            &mut horizontal
+        };
+        marks[y * manifold.width + x] = true;
+    };
+    for state in (0..seen.len()).filter(|&i| seen[i]) {
+        let (x, y, dir) = manifold.cell(state);
+        mark(x, y, dir);
+        // Beams coming out of splitters pass the cells next to them
+        if input[y][x] == '^' {
+            for m in manifold.lateral(x, y, dir) {
+                if let Move::Cell(lx, ly) = m {
+                    mark(lx, ly, dir);
+                }
+            }
         }
-        if let Some(&s) = steps.get(&current) {
-            return s;
+    }
+    let mut text = String::new();
+    for (y, row) in input.iter().enumerate() {
+        for (x, &c) in row.iter().enumerate() {
+            let i = y * manifold.width + x;
+            text.push(match (c, vertical[i], horizontal[i]) {
+                ('.', true, true) => '+',
+                ('.', true, false) => '|',
+                ('.', false, true) => '-',
+                _ => c,
+            });
         }
-        let result = edges
+        text.push('\n');
+    }
+    text
+}
+
+// Number of timelines passing through each cell, which stops growing at
+// u128::MAX. Beams looping forever would make it infinite.
+pub fn heat(input: &[Vec<char>], params: &Params) -> Result<Vec<Vec<u128>>, BeamError> {
+    let manifold = Manifold::new(input, params);
+    let seen = manifold.reached();
+    let successors = |state: usize| {
+        let (x, y, dir) = manifold.cell(state);
+        manifold
+            .next(x, y, dir)
+            .into_iter()
+            .filter_map(|(m, d)| match m {
+                Move::Cell(nx, ny) => Some(manifold.state(nx, ny, d)),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // Add up the timelines reaching each state, in an order where every state
+    // comes after all the states leading to it
+    let mut incoming = vec![0; seen.len()];
+    for state in (0..seen.len()).filter(|&i| seen[i]) {
+        successors(state)
             .iter()
-            .filter_map(|(from, to)| {
-                if from == &current {
-                    Some(recursive(*to, ends, edges, steps))
-                } else {
-                    None
+            .for_each(|&next| incoming[next] += 1);
+    }
+    let mut counts = vec![0u128; seen.len()];
+    for (m, dir) in manifold.starts() {
+        if let Move::Cell(x, y) = m {
+            let state = manifold.state(x, y, dir);
+            counts[state] = counts[state].saturating_add(1);
+        }
+    }
+    let mut ready = (0..seen.len())
+        .filter(|&i| seen[i] && incoming[i] == 0)
+        .collect::<Vec<_>>();
+    let mut heat = vec![vec![0u128; manifold.width]; manifold.height];
+    while let Some(state) = ready.pop() {
+        let (x, y, dir) = manifold.cell(state);
+        heat[y][x] = heat[y][x].saturating_add(counts[state]);
+        if input[y][x] == '^' {
+            for m in manifold.lateral(x, y, dir) {
+                if let Move::Cell(lx, ly) = m {
+                    heat[ly][lx] = heat[ly][lx].saturating_add(counts[state]);
                 }
-            })
-            .sum();
-        //println!("At {:?}, found {} paths", current, result);
-        steps.insert(current, result);
-        result
+            }
+        }
+        for next in successors(state) {
+            counts[next] = counts[next].saturating_add(counts[state]);
+            incoming[next] -= 1;
+            if incoming[next] == 0 {
+                ready.push(next);
+            }
+        }
+    }
+    // States still waiting for others are on a loop, or after one
+    match (0..seen.len()).find(|&i| seen[i] && incoming[i] > 0) {
+        Some(state) => {
+            let (x, y, _) = manifold.cell(state);
+            Err(BeamError::Cycle(x, y))
+        }
+        None => Ok(heat),
+    }
+}
+
+// Color for a timeline count on a log scale, as counts can be huge
+fn heat_color(count: u128, max: u128) -> render::Rgb {
+    render::gradient((count as f64).ln_1p() / (max as f64).ln_1p().max(f64::MIN_POSITIVE))
+}
+
+// The manifold with every cell colored by its timeline count, using ANSI
+// escape codes
+pub fn heatmap_text(input: &[Vec<char>], heat: &[Vec<u128>]) -> String {
+    let max = heat.iter().flatten().copied().max().unwrap_or(0);
+    let mut text = String::new();
+    for (row, counts) in input.iter().zip(heat) {
+        for (&c, &count) in row.iter().zip(counts) {
+            if count == 0 {
+                text.push(c);
+            } else {
+                let [r, g, b] = heat_color(count, max);
+                text.push_str(&format!("\x1b[48;2;{r};{g};{b}m{c}\x1b[0m"));
+            }
+        }
+        text.push('\n');
     }
+    text
+}
+
+// One pixel per cell, with tiles no beam reaches in gray
+pub fn render_heat(input: &[Vec<char>], heat: &[Vec<u128>]) -> Image {
+    let max = heat.iter().flatten().copied().max().unwrap_or(0);
+    let mut image = Image::new(input[0].len(), input.len(), render::BLACK);
+    for (y, (row, counts)) in input.iter().zip(heat).enumerate() {
+        for (x, (&c, &count)) in row.iter().zip(counts).enumerate() {
+            let color = match (c, count) {
+                ('.', 0) => render::BLACK,
+                (_, 0) => render::GRAY,
+                _ => heat_color(count, max),
+            };
+            image.set(x, y, color);
+        }
+    }
+    image
+}
 
-    recursive((start, 0), &ends, &edges, &mut steps)
+pub fn part1(input: &[Vec<char>], params: &Params) -> i64 {
+    beams(input, params).splits
+}
+
+pub fn part2(input: &[Vec<char>], params: &Params) -> Result<u128, BeamError> {
+    beams(input, params).timelines
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc2025::{
+        check,
+        generate::{self, Day07Size},
+        reference,
+    };
 
     #[test]
     fn test_part1() {
         let input = _sample_input();
-        assert_eq!(21, part1(&input));
+        assert_eq!(21, part1(&input, &Params::default()));
     }
 
     #[test]
     fn test_part2() {
         let input = _sample_input();
-        assert_eq!(40, part2(&input));
+        assert_eq!(Ok(40), part2(&input, &Params::default()));
+    }
+
+    #[test]
+    fn test_sweep() {
+        // Every beam reaches a splitter on every other row, so the timelines
+        // double each time
+        let levels = 100;
+        let width = 2 * levels + 3;
+        let center = width / 2;
+        let mut lines = vec![String::new(); 2 * levels + 2];
+        for (y, line) in lines.iter_mut().enumerate() {
+            *line = (0..width)
+                .map(|x| match y {
+                    0 if x == center => 'S',
+                    _ if y % 2 == 0 && y > 0 && (x + y / 2 + center) % 2 == 1 => '^',
+                    _ => '.',
+                })
+                .collect();
+        }
+        let input = process_input(lines).unwrap();
+        let params = Params::default();
+        let expected = Beams {
+            splits: 5050,
+            timelines: Ok(1 << 100),
+        };
+        assert_eq!(expected, sweep(&input, &params));
+        assert_eq!(expected, trace(&input, &params));
+
+        // Doubling 128 times doesn't fit
+        let input = process_input(
+            std::iter::once("S.".to_string())
+                .chain((0..128).map(|_| "^^".to_string()))
+                .collect(),
+        )
+        .unwrap();
+        let params = Params {
+            offsets: vec![-1, 1],
+            wrap: true,
+        };
+        assert_eq!(Err(BeamError::Overflow), sweep(&input, &params).timelines);
+        assert_eq!(Err(BeamError::Overflow), trace(&input, &params).timelines);
+        assert_eq!(128, sweep(&input, &params).splits);
+    }
+
+    #[test]
+    fn test_semantics() {
+        let lines = |data: &str| data.split('\n').map(|s| s.to_string()).collect();
+        let run = |data: &str, offsets: &[i64], wrap: bool| {
+            let params = Params {
+                offsets: offsets.to_vec(),
+                wrap,
+            };
+            let beams = beams(&process_input(lines(data)).unwrap(), &params);
+            (beams.splits, beams.timelines)
+        };
+        // Mirrors turn the beam right, then down again
+        assert_eq!((0, Ok(1)), run("S..\n\\.\\\n...", &[-1, 1], false));
+        // Absorbers stop beams
+        assert_eq!((1, Ok(1)), run(".S.\n.^.\n#..", &[-1, 1], false));
+        // Every source sends a beam down, from any row
+        assert_eq!((0, Ok(2)), run("S..\n.S.\n...", &[-1, 1], false));
+        // Splitters can send beams in more directions, including straight on
+        assert_eq!((1, Ok(3)), run("..S..\n..^..\n.....", &[-1, 0, 1], false));
+        assert_eq!((1, Ok(2)), run("..S..\n..^..\n.....", &[-2, 2], false));
+        // Beams leaving a side come back in on the other when wrapping
+        assert_eq!((1, Ok(1)), run("S..\n^..\n...", &[-1, 1], false));
+        assert_eq!((1, Ok(2)), run("S..\n^..\n...", &[-1, 1], true));
+        // A splitter inside a loop of mirrors keeps feeding it
+        assert_eq!(
+            (2, Err(BeamError::Cycle(3, 1))),
+            run(".S..\n/..\\\n\\\\^/\n/^..", &[-1, 1], false)
+        );
+
+        // Without mirrors, following beams in every direction agrees with the
+        // sweep
+        let mut rng = generate::Rng::new(47);
+        for _ in 0..300 {
+            let (width, height) = (rng.index(6) + 1, rng.index(6) + 1);
+            let data = (0..height)
+                .map(|_| {
+                    (0..width)
+                        .map(|_| ['.', '.', '^', '#', 'S'][rng.index(5)])
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>();
+            let Ok(input) = process_input(data) else {
+                continue;
+            };
+            let offsets = (0..rng.index(3) + 1)
+                .map(|_| rng.range(-2, 2))
+                .collect::<Vec<_>>();
+            let params = Params {
+                offsets,
+                wrap: rng.chance(0.5),
+            };
+            assert_eq!(sweep(&input, &params), trace(&input, &params));
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let input = _sample_input();
+        let params = Params::default();
+        assert_eq!(
+            ".......S.......
+.......|.......
+......|^|......
+......|.|......
+.....|^|^|.....
+.....|.|.|.....
+....|^|^|^|....
+....|.|.|.|....
+...|^|^|||^|...
+...|.|.|||.|...
+..|^|^|||^|^|..
+..|.|.|||.|.|..
+.|^|||^||.||^|.
+.|.|||.||.||.|.
+|^|^|^|^|^|||^|
+|.|.|.|.|.|||.|
+",
+            draw_beams(&input, &params)
+        );
+        // 21 splitters get beams, and none of the other splitters do
+        let drawn = draw_beams(&input, &params);
+        let lines = drawn.lines().collect::<Vec<_>>();
+        let hit = (1..lines.len())
+            .flat_map(|y| (0..lines[y].len()).map(move |x| (x, y)))
+            .filter(|&(x, y)| lines[y].as_bytes()[x] == b'^' && lines[y - 1].as_bytes()[x] == b'|')
+            .count();
+        assert_eq!(21, hit);
+
+        // The 40 timelines all leave through the bottom row
+        let counts = heat(&input, &params).unwrap();
+        assert_eq!(40, counts.last().unwrap().iter().sum::<u128>());
+        assert_eq!(1, counts[1][7]);
+        assert_eq!(0, counts[1][6]);
+        let image = render_heat(&input, &counts);
+        assert_eq!(render::BLACK, image.get(0, 0));
+        let max = *counts.iter().flatten().max().unwrap();
+        assert_eq!(heat_color(1, max), image.get(7, 1));
+        assert_eq!(render::gradient(1.0), heat_color(max, max));
+        let [r, g, b] = heat_color(1, max);
+        let text = heatmap_text(&input, &counts);
+        assert!(text.starts_with(&format!(
+            ".......S.......\n.......\x1b[48;2;{r};{g};{b}m.\x1b[0m.......\n"
+        )));
+
+        // Mirrors draw sideways beams, and loops have no heatmap
+        let lines = |data: &str| data.split('\n').map(|s| s.to_string()).collect();
+        let input = process_input(lines("S..\n\\.\\\n...")).unwrap();
+        assert_eq!("S..\n\\-\\\n..|\n", draw_beams(&input, &params));
+        let input = process_input(lines(".S..\n/..\\\n\\\\^/\n/^..")).unwrap();
+        assert!(matches!(heat(&input, &params), Err(BeamError::Cycle(_, _))));
+    }
+
+    #[test]
+    fn parse_errors() {
+        let lines = |data: &str| data.split('\n').map(|s| s.to_string()).collect();
+        assert!(process_input(lines("...\n.^.")).is_err());
+        assert!(process_input(lines("..S\n.^")).is_err());
+        assert!(process_input(lines("S.x")).is_err());
+        assert!(process_input(lines("S..\n.S.")).is_ok());
+        assert!(process_input(lines("S..\n.^|")).is_err());
+    }
+
+    #[test]
+    fn differential() {
+        let size = Day07Size {
+            width: 15,
+            height: 16,
+            density: 0.4,
+        };
+        check::differential(
+            200,
+            |rng| generate::day07(rng, &size).unwrap(),
+            process_input,
+            |input| {
+                let params = Params::default();
+                (part1(input, &params), part2(input, &params).unwrap())
+            },
+            |input| {
+                (
+                    reference::day07::part1(input),
+                    reference::day07::part2(input) as u128,
+                )
+            },
+        );
+
+        // A manifold needs a column for the source
+        let mut rng = generate::Rng::new(1);
+        let empty = Day07Size { width: 0, ..size };
+        assert_eq!(
+            Err("width must be at least 1, got 0".to_string()),
+            generate::day07(&mut rng, &empty)
+        );
     }
 
     #[test]
     fn real_part1() {
         let input = day_input();
-        assert_eq!(1539, part1(&input));
+        assert_eq!(1539, part1(&input, &Params::default()));
     }
 
     #[test]
     fn real_part2() {
         let input = day_input();
-        assert_eq!(6479180385864, part2(&input));
+        assert_eq!(Ok(6479180385864), part2(&input, &Params::default()));
     }
 }
@@ -1,31 +1,93 @@
-use std::collections::HashMap;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::Range,
+};
 
-use aoc2025::common::read_input;
-use itertools::Itertools;
+use aoc2025::common::{Args, ParseError, read_input};
 use regex::Regex;
+use serde::Serialize;
 
 const DAY: u32 = 8;
+const MAX_COORD: i64 = 1_000_000_000;
+// Most tied pairs that are tried in every combination when checking for
+// ambiguous answers
+const MAX_TIED: usize = 20;
+
+// Puzzle parameters, overridable with `--param key=value`
+#[derive(Clone, Copy, Debug)]
+pub struct Params {
+    // Number of shortest connections to make in part 1
+    connections: usize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params { connections: 1000 }
+    }
+}
+
+impl Params {
+    fn from_args(args: &Args) -> Params {
+        args.expect_params(&["connections"]);
+        let default = Params::default();
+        Params {
+            connections: args.param("connections", default.connections),
+        }
+    }
+}
 
 pub fn main() {
+    let args = Args::from_env();
+    args.expect_options(&["report", "check"]);
+    let params = Params::from_args(&args);
     let input = day_input();
-    let part1_result = part1(&input, false);
+    if args.flag("report") {
+        let report = Clustering::new(&input).report(params.connections);
+        match args.option("report") {
+            Some("json") => println!("{}", serde_json::to_string_pretty(&report).unwrap()),
+            Some(format) => panic!("Unknown format {format}, expected json"),
+            None => print!("{}", report_text(&report)),
+        }
+    }
+    if args.flag("check") {
+        let ambiguities = Clustering::new(&input).ambiguities(params.connections);
+        if ambiguities.is_empty() {
+            println!("No ties make the answers ambiguous");
+        }
+        for ambiguity in &ambiguities {
+            println!("{}", ambiguity_text(ambiguity));
+        }
+    }
+    let part1_result = part1(&input, &params);
     println!("Day {DAY}, part 1: {part1_result}");
     let part2_result = part2(&input);
     println!("Day {DAY}, part 2: {part2_result}");
 }
 
 // Parse input
-fn process_input(input: Vec<String>) -> Vec<(i64, i64, i64)> {
+pub fn process_input(input: Vec<String>) -> Result<Vec<(i64, i64, i64)>, ParseError> {
     let pattern = Regex::new(r"^(\d+),(\d+),(\d+)$").unwrap();
+    if input.is_empty() {
+        return Err(ParseError::at(0, "No junction boxes"));
+    }
     input
         .iter()
-        .map(|row| {
-            let caps = pattern.captures(row).unwrap();
-            (
-                caps[1].parse().unwrap(),
-                caps[2].parse().unwrap(),
-                caps[3].parse().unwrap(),
-            )
+        .enumerate()
+        .map(|(i, row)| {
+            let caps = pattern
+                .captures(row)
+                .ok_or_else(|| ParseError::at(i, format!("Expected x,y,z, got {row}")))?;
+            let parse = |s: &str| {
+                let c = s
+                    .parse::<i64>()
+                    .map_err(|e| ParseError::at(i, format!("Invalid coordinate {s}: {e}")))?;
+                // Keeps the squared distances within an i64
+                if c > MAX_COORD {
+                    return Err(ParseError::at(i, format!("Coordinate {c} is too large")));
+                }
+                Ok(c)
+            };
+            Ok((parse(&caps[1])?, parse(&caps[2])?, parse(&caps[3])?))
         })
         .collect()
 }
@@ -55,119 +117,353 @@ fn _sample_input() -> Vec<(i64, i64, i64)> {
         .split('\n')
         .map(|s| s.to_string())
         .collect();
-    process_input(data)
+    process_input(data).unwrap()
 }
 
 // Parse day's input
 fn day_input() -> Vec<(i64, i64, i64)> {
-    process_input(read_input(DAY))
+    process_input(read_input(DAY)).unwrap_or_else(|e| panic!("Invalid input: {e}"))
 }
 
-// Calculate all distances between nodes
-fn distances(nodes: &Vec<(i64, i64, i64)>) -> Vec<(usize, usize, i64)> {
+// Calculate the squared distances between all pairs of nodes, in the order
+// they are connected: shortest first, and pairs at the same distance by their
+// first node and then their second node, both in input order
+fn distances(nodes: &[(i64, i64, i64)]) -> Vec<(usize, usize, i64)> {
     let mut dists = Vec::new();
     for i in 0..nodes.len() {
         for j in i + 1..nodes.len() {
             let dx = nodes[i].0 - nodes[j].0;
             let dy = nodes[i].1 - nodes[j].1;
             let dz = nodes[i].2 - nodes[j].2;
-            let dist = (dx * dx + dy * dy + dz * dz).isqrt();
-            dists.push((i, j, dist))
+            dists.push((i, j, dx * dx + dy * dy + dz * dz))
         }
     }
-    dists.sort_by(|a, b| a.2.cmp(&b.2));
+    dists.sort_by_key(|&(i, j, dist)| (dist, i, j));
     dists
 }
 
-fn part1(input: &Vec<(i64, i64, i64)>, test: bool) -> i64 {
-    let dists = distances(input);
-    let mut circuits = HashMap::new();
-    let target_connections = if test { 10 } else { 1000 };
-    dists
-        .iter()
-        .take(target_connections)
-        .for_each(|(from, to, _)| {
-            // Check if both nodes are already in the same circuit
-            if circuits.contains_key(from) && circuits.get(from) == circuits.get(to) {
-                return;
-            }
+// Circuits of junction boxes, merged by connecting them
+#[derive(Clone)]
+struct DisjointSet {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(count: usize) -> DisjointSet {
+        DisjointSet {
+            parent: (0..count).collect(),
+            size: vec![1; count],
+        }
+    }
+
+    fn find(&mut self, mut node: usize) -> usize {
+        while self.parent[node] != node {
+            self.parent[node] = self.parent[self.parent[node]];
+            node = self.parent[node];
+        }
+        node
+    }
 
-            // Merge circuits if needed
-            let from_c = circuits.get(from);
-            let to_c = circuits.get(to);
-            if from_c.is_some() && to_c.is_none() {
-                circuits.insert(*to, *from_c.unwrap());
-            } else if from_c.is_none() && to_c.is_some() {
-                circuits.insert(*from, *to_c.unwrap());
-            } else if from_c.is_some() && to_c.is_some() {
-                let old_c = *to_c.unwrap();
-                let new_c = *from_c.unwrap();
-                circuits.iter_mut().for_each(|(_, v)| {
-                    if *v == old_c {
-                        *v = new_c;
+    // Merge the circuits of the two nodes, or return false if they already
+    // are the same
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        true
+    }
+
+    // Product of the sizes of the largest circuits
+    fn largest_product(&self, circuits: usize) -> i64 {
+        let mut sizes = (0..self.parent.len())
+            .filter(|&node| self.parent[node] == node)
+            .map(|node| self.size[node])
+            .collect::<Vec<_>>();
+        sizes.sort_by(|a, b| b.cmp(a));
+        sizes.iter().take(circuits).product::<usize>() as i64
+    }
+}
+
+// Connection that joins two circuits
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Edge {
+    pub from: usize,
+    pub to: usize,
+    pub squared_distance: i64,
+    // Position in the order connections are made, counting from 1
+    pub connection: usize,
+}
+
+// Circuits after a number of connections, and how they all end up connected
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Report {
+    pub connections: usize,
+    // Junction boxes in each circuit, largest circuits first
+    pub circuits: Vec<Vec<usize>>,
+    // Number of circuits of each size
+    pub sizes: BTreeMap<usize, usize>,
+    pub connected_after: usize,
+    pub spanning_tree: Vec<Edge>,
+}
+
+// Tied pairs that could make an answer come out differently
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Ambiguity {
+    pub part: u32,
+    // Connection the answer depends on, counting from 1
+    pub connection: usize,
+    pub squared_distance: i64,
+    pub tied: Vec<(usize, usize)>,
+    // Every answer some order of the tied pairs gives, unless there are too
+    // many tied pairs to try
+    pub answers: Option<Vec<i64>>,
+}
+
+// Junction boxes, connected shortest distance first as in Kruskal's algorithm
+pub struct Clustering {
+    count: usize,
+    boxes: Vec<(i64, i64, i64)>,
+    pairs: Vec<(usize, usize, i64)>,
+}
+
+impl Clustering {
+    pub fn new(boxes: &[(i64, i64, i64)]) -> Clustering {
+        Clustering {
+            count: boxes.len(),
+            boxes: boxes.to_vec(),
+            pairs: distances(boxes),
+        }
+    }
+
+    // Pairs at the same distance as the pair at the index
+    fn tie(&self, i: usize) -> Range<usize> {
+        let dist = self.pairs[i].2;
+        self.pairs.partition_point(|p| p.2 < dist)..self.pairs.partition_point(|p| p.2 <= dist)
+    }
+
+    // Circuits after all the connections before the tie, and the tied pairs
+    // that would join two of them
+    fn before_tie(&self, tie: &Range<usize>) -> (DisjointSet, Vec<(usize, usize)>) {
+        let mut circuits = DisjointSet::new(self.count);
+        for &(from, to, _) in &self.pairs[..tie.start] {
+            circuits.union(from, to);
+        }
+        let joining = self.pairs[tie.clone()]
+            .iter()
+            .filter(|&&(from, to, _)| circuits.find(from) != circuits.find(to))
+            .map(|&(from, to, _)| (from, to))
+            .collect();
+        (circuits, joining)
+    }
+
+    fn ambiguity(&self, part: u32, connection: usize, answers: Option<BTreeSet<i64>>) -> Ambiguity {
+        let tie = self.tie(connection - 1);
+        Ambiguity {
+            part,
+            connection,
+            squared_distance: self.pairs[tie.start].2,
+            tied: self.pairs[tie].iter().map(|&(a, b, _)| (a, b)).collect(),
+            answers: answers.map(|a| a.into_iter().collect()),
+        }
+    }
+
+    // Places where pairs at the same distance could be connected in another
+    // order and change an answer: pairs straddling the part 1 cutoff, and
+    // pairs that could each be the one connecting everything in part 2
+    pub fn ambiguities(&self, connections: usize) -> Vec<Ambiguity> {
+        let mut ambiguities = Vec::new();
+        let n = connections.min(self.pairs.len());
+        if n > 0 && n < self.pairs.len() && self.pairs[n - 1].2 == self.pairs[n].2 {
+            let tie = self.tie(n - 1);
+            let taken = n - tie.start;
+            let (circuits, joining) = self.before_tie(&tie);
+            // Tied pairs within a circuit change nothing, but still count as
+            // connections
+            let within = tie.len() - joining.len();
+            let answers = (joining.len() <= MAX_TIED).then(|| {
+                let mut answers = BTreeSet::new();
+                for mask in 0u32..1 << joining.len() {
+                    let chosen = mask.count_ones() as usize;
+                    if chosen > taken || taken - chosen > within {
+                        continue;
                     }
-                });
-            } else {
-                // Create new circuit
-                circuits.insert(*from, *from);
-                circuits.insert(*to, *from);
-            }
-        });
-    let mut counts: HashMap<usize, usize> = HashMap::new();
-    for &v in circuits.values() {
-        *counts.entry(v).or_insert(0) += 1;
-    }
-    let values = counts
-        .into_iter()
-        .map(|(_, c)| c)
-        .sorted_by(|a, b| b.cmp(a));
-    values.take(3).product::<usize>() as i64
-}
-
-fn part2(input: &Vec<(i64, i64, i64)>) -> i64 {
-    let dists = distances(input);
-    let mut circuits = HashMap::new();
-    // Keep track of the last necessary connection
-    let mut last_connected = (0, 0);
-    dists.iter().for_each(|(from, to, _)| {
-        // Check if both nodes are already in the same circuit
-        if circuits.contains_key(from) && circuits.get(from) == circuits.get(to) {
-            return;
-        }
-        last_connected = (*from, *to);
-
-        // Merge circuits if needed
-        let from_c = circuits.get(from);
-        let to_c = circuits.get(to);
-        if from_c.is_some() && to_c.is_none() {
-            circuits.insert(*to, *from_c.unwrap());
-        } else if from_c.is_none() && to_c.is_some() {
-            circuits.insert(*from, *to_c.unwrap());
-        } else if from_c.is_some() && to_c.is_some() {
-            let old_c = *to_c.unwrap();
-            let new_c = *from_c.unwrap();
-            circuits.iter_mut().for_each(|(_, v)| {
-                if *v == old_c {
-                    *v = new_c;
+                    let mut circuits = circuits.clone();
+                    for (i, &(from, to)) in joining.iter().enumerate() {
+                        if mask >> i & 1 == 1 {
+                            circuits.union(from, to);
+                        }
+                    }
+                    answers.insert(circuits.largest_product(3));
                 }
+                answers
             });
-        } else {
-            // Create new circuit
-            circuits.insert(*from, *from);
-            circuits.insert(*to, *from);
+            if answers.as_ref().is_none_or(|a| a.len() > 1) {
+                ambiguities.push(self.ambiguity(1, n, answers));
+            }
+        }
+        if let Some(last) = self.spanning_tree().last() {
+            // Any tied pair joining two circuits is in some spanning tree, so
+            // it can be the last connection
+            let (_, joining) = self.before_tie(&self.tie(last.connection - 1));
+            let answers = joining
+                .iter()
+                .map(|&(from, to)| self.boxes[from].0 * self.boxes[to].0)
+                .collect::<BTreeSet<_>>();
+            if answers.len() > 1 {
+                ambiguities.push(self.ambiguity(2, last.connection, Some(answers)));
+            }
+        }
+        ambiguities
+    }
+
+    // Connections that join two circuits, which form a minimum spanning tree
+    pub fn spanning_tree(&self) -> Vec<Edge> {
+        let mut circuits = DisjointSet::new(self.count);
+        let mut edges = Vec::new();
+        for (i, &(from, to, squared_distance)) in self.pairs.iter().enumerate() {
+            if edges.len() + 1 == self.count {
+                break;
+            }
+            if circuits.union(from, to) {
+                edges.push(Edge {
+                    from,
+                    to,
+                    squared_distance,
+                    connection: i + 1,
+                });
+            }
         }
-    });
-    input[last_connected.0].0 as i64 * input[last_connected.1].0 as i64
+        edges
+    }
+
+    // Circuit of each junction box after the first connections, numbered by
+    // their smallest junction box
+    pub fn membership(&self, connections: usize) -> Vec<usize> {
+        let mut circuits = DisjointSet::new(self.count);
+        for &(from, to, _) in self.pairs.iter().take(connections) {
+            circuits.union(from, to);
+        }
+        let mut smallest = vec![usize::MAX; self.count];
+        for node in 0..self.count {
+            let root = circuits.find(node);
+            smallest[root] = smallest[root].min(node);
+        }
+        (0..self.count)
+            .map(|node| smallest[circuits.find(node)])
+            .collect()
+    }
+
+    // Junction boxes in each circuit after the first connections, largest
+    // circuits first
+    pub fn circuits(&self, connections: usize) -> Vec<Vec<usize>> {
+        let mut circuits: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for (node, circuit) in self.membership(connections).into_iter().enumerate() {
+            circuits.entry(circuit).or_default().push(node);
+        }
+        let mut circuits = circuits.into_values().collect::<Vec<_>>();
+        circuits.sort_by_key(|c| std::cmp::Reverse(c.len()));
+        circuits
+    }
+
+    // Number of connections after which every junction box is in one circuit.
+    // Every pair can be connected, so that always happens eventually.
+    pub fn connected_after(&self) -> usize {
+        last_connection(&self.spanning_tree())
+    }
+
+    pub fn report(&self, connections: usize) -> Report {
+        let circuits = self.circuits(connections);
+        let mut sizes = BTreeMap::new();
+        for circuit in &circuits {
+            *sizes.entry(circuit.len()).or_insert(0) += 1;
+        }
+        let spanning_tree = self.spanning_tree();
+        Report {
+            connections,
+            circuits,
+            sizes,
+            connected_after: last_connection(&spanning_tree),
+            spanning_tree,
+        }
+    }
+}
+
+// Connection that completes the spanning tree, or 0 when there's nothing to connect
+fn last_connection(spanning_tree: &[Edge]) -> usize {
+    spanning_tree.last().map_or(0, |edge| edge.connection)
+}
+
+// Summary of the report, without the circuit members
+pub fn report_text(report: &Report) -> String {
+    let sizes = report
+        .sizes
+        .iter()
+        .rev()
+        .map(|(size, count)| format!("{count} of size {size}"))
+        .collect::<Vec<_>>();
+    format!(
+        "After {} connections there are {} circuits: {}\n\
+         The minimum spanning tree has {} connections\n\
+         All junction boxes are connected after {} connections\n",
+        report.connections,
+        report.circuits.len(),
+        sizes.join(", "),
+        report.spanning_tree.len(),
+        report.connected_after
+    )
+}
+
+pub fn ambiguity_text(ambiguity: &Ambiguity) -> String {
+    let answers = match &ambiguity.answers {
+        Some(answers) => answers
+            .iter()
+            .map(|a| a.to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+        None => "too many tied pairs to tell".to_string(),
+    };
+    format!(
+        "Part {}: connection {} is one of {} pairs at squared distance {}, possible answers: {answers}",
+        ambiguity.part,
+        ambiguity.connection,
+        ambiguity.tied.len(),
+        ambiguity.squared_distance
+    )
+}
+
+pub fn part1(input: &[(i64, i64, i64)], params: &Params) -> i64 {
+    let circuits = Clustering::new(input).circuits(params.connections);
+    circuits.iter().take(3).map(|c| c.len()).product::<usize>() as i64
+}
+
+pub fn part2(input: &[(i64, i64, i64)]) -> i64 {
+    // The last connection of the spanning tree connects everything
+    match Clustering::new(input).spanning_tree().last() {
+        Some(edge) => input[edge.from].0 * input[edge.to].0,
+        None => 0,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc2025::{
+        check,
+        generate::{self, Day08Size},
+        reference,
+    };
 
     #[test]
     fn test_part1() {
         let input = _sample_input();
-        assert_eq!(40, part1(&input, true));
+        assert_eq!(40, part1(&input, &Params { connections: 10 }));
     }
 
     #[test]
@@ -176,10 +472,191 @@ mod tests {
         assert_eq!(25272, part2(&input));
     }
 
+    #[test]
+    fn test_clustering() {
+        let input = _sample_input();
+        let clustering = Clustering::new(&input);
+        let tree = clustering.spanning_tree();
+        assert_eq!(19, tree.len());
+        assert_eq!((0, 19), (tree[0].from, tree[0].to));
+        assert_eq!((10, 12), (tree[18].from, tree[18].to));
+        assert_eq!(tree[18].connection, clustering.connected_after());
+        assert!(
+            tree.windows(2)
+                .all(|w| w[0].squared_distance <= w[1].squared_distance)
+        );
+
+        // The first two connections join 0, 19 and 7, the third one joins 2
+        // and 13, and the fourth one is between boxes already connected
+        let membership = clustering.membership(4);
+        assert_eq!(membership[19], 0);
+        assert_eq!(membership[7], 0);
+        assert_eq!(membership[13], 2);
+        assert_eq!(membership[1], 1);
+        let report = clustering.report(10);
+        assert_eq!(11, report.circuits.len());
+        assert_eq!(
+            vec![5, 4, 2, 2],
+            report.circuits[..4]
+                .iter()
+                .map(|c| c.len())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            BTreeMap::from([(1, 7), (2, 2), (4, 1), (5, 1)]),
+            report.sizes
+        );
+        assert_eq!(vec![0, 7, 14, 19], report.circuits[1]);
+        let text = report_text(&report);
+        assert!(text.starts_with(
+            "After 10 connections there are 11 circuits: 1 of size 5, 1 of size 4, 2 of size 2, 7 of size 1\n"
+        ));
+        let json: serde_json::Value = serde_json::to_value(&report).unwrap();
+        assert_eq!(1, json["sizes"]["5"]);
+        assert_eq!(19, json["spanning_tree"].as_array().unwrap().len());
+
+        // A single junction box is connected from the start
+        let single = Clustering::new(&[(1, 2, 3)]);
+        assert_eq!(0, single.connected_after());
+        assert!(single.spanning_tree().is_empty());
+    }
+
+    #[test]
+    fn test_ties() {
+        // Distances only a fraction apart are still ordered correctly:
+        // 5² + 5² + 5² = 75 and 8² + 3² + 1² = 74 both truncate to 8
+        let boxes = [(0, 0, 0), (5, 5, 5), (100, 100, 100), (108, 103, 101)];
+        let clustering = Clustering::new(&boxes);
+        let tree = clustering.spanning_tree();
+        assert_eq!(
+            (2, 3, 74),
+            (tree[0].from, tree[0].to, tree[0].squared_distance)
+        );
+        assert_eq!(
+            (0, 1, 75),
+            (tree[1].from, tree[1].to, tree[1].squared_distance)
+        );
+
+        // Ties are broken by the first box and then the second one
+        let line = [(0, 0, 0), (1, 0, 0), (2, 0, 0), (10, 0, 0), (11, 0, 0)];
+        let clustering = Clustering::new(&line);
+        let order = clustering.pairs[..3]
+            .iter()
+            .map(|&(a, b, _)| (a, b))
+            .collect::<Vec<_>>();
+        assert_eq!(vec![(0, 1), (1, 2), (3, 4)], order);
+        assert_eq!(3, part1(&line, &Params { connections: 2 }));
+
+        // Two of the three pairs at distance 1 are connected, which can make a
+        // circuit of 3 or two of 2
+        let ambiguities = clustering.ambiguities(2);
+        assert_eq!(
+            vec![Ambiguity {
+                part: 1,
+                connection: 2,
+                squared_distance: 1,
+                tied: vec![(0, 1), (1, 2), (3, 4)],
+                answers: Some(vec![3, 4]),
+            }],
+            ambiguities
+        );
+        assert_eq!(
+            "Part 1: connection 2 is one of 3 pairs at squared distance 1, possible answers: 3, 4",
+            ambiguity_text(&ambiguities[0])
+        );
+        assert!(clustering.ambiguities(3).is_empty());
+        assert!(clustering.ambiguities(1).is_empty());
+
+        // Either pair at distance 10 can connect everything
+        let boxes = [(0, 0, 0), (10, 0, 0), (20, 0, 0)];
+        assert_eq!(200, part2(&boxes));
+        let ambiguities = Clustering::new(&boxes).ambiguities(5);
+        assert_eq!(1, ambiguities.len());
+        assert_eq!((2, 2), (ambiguities[0].part, ambiguities[0].connection));
+        assert_eq!(Some(vec![0, 200]), ambiguities[0].answers);
+
+        // Ties that can't change anything aren't reported
+        let pairs = [
+            (0, 0, 0),
+            (1, 0, 0),
+            (10, 0, 0),
+            (11, 0, 0),
+            (30, 0, 0),
+            (31, 0, 0),
+        ];
+        assert!(Clustering::new(&pairs).ambiguities(2).is_empty());
+        assert!(Clustering::new(&_sample_input()).ambiguities(10).is_empty());
+
+        // Connecting tied pairs in any other order only gives reported answers
+        let mut rng = generate::Rng::new(50);
+        for _ in 0..200 {
+            let boxes = (0..rng.range(2, 8))
+                .map(|_| (rng.range(0, 3), rng.range(0, 3), rng.range(0, 2)))
+                .collect::<Vec<_>>();
+            let connections = rng.index(10) + 1;
+            let clustering = Clustering::new(&boxes);
+            let ambiguities = clustering.ambiguities(connections);
+            let possible =
+                |part: u32, answer: i64| match ambiguities.iter().find(|a| a.part == part) {
+                    Some(ambiguity) => ambiguity.answers.as_ref().unwrap().contains(&answer),
+                    None => false,
+                };
+            let expected = (part1(&boxes, &Params { connections }), part2(&boxes));
+            for _ in 0..20 {
+                let mut shuffled = Clustering::new(&boxes);
+                let mut start = 0;
+                while start < shuffled.pairs.len() {
+                    let tie = shuffled.tie(start);
+                    rng.shuffle(&mut shuffled.pairs[tie.clone()]);
+                    start = tie.end;
+                }
+                let mut circuits = DisjointSet::new(boxes.len());
+                for &(from, to, _) in shuffled.pairs.iter().take(connections) {
+                    circuits.union(from, to);
+                }
+                let answer1 = circuits.largest_product(3);
+                assert!(answer1 == expected.0 || possible(1, answer1));
+                let last = shuffled.spanning_tree().last().copied();
+                let answer2 = last.map_or(0, |e| boxes[e.from].0 * boxes[e.to].0);
+                assert!(answer2 == expected.1 || possible(2, answer2));
+            }
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        let lines = |data: &str| data.split('\n').map(|s| s.to_string()).collect();
+        assert!(process_input(lines("1,2")).is_err());
+        assert!(process_input(lines("")).is_err());
+        assert!(process_input(lines("1,2,99999999999999999999")).is_err());
+        assert!(process_input(lines("1,2,1000000001")).is_err());
+    }
+
+    #[test]
+    fn differential() {
+        let size = Day08Size {
+            boxes: 20,
+            max_coord: 1000,
+        };
+        let params = Params { connections: 10 };
+        check::differential(
+            200,
+            |rng| generate::day08(rng, &size).unwrap(),
+            process_input,
+            |input| (part1(input, &params), part2(input)),
+            |input| {
+                (
+                    reference::day08::part1(input, params.connections),
+                    reference::day08::part2(input),
+                )
+            },
+        );
+    }
+
     #[test]
     fn real_part1() {
         let input = day_input();
-        assert_eq!(129564, part1(&input, false));
+        assert_eq!(129564, part1(&input, &Params::default()));
     }
 
     #[test]
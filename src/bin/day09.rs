@@ -1,27 +1,70 @@
-use aoc2025::common::read_input;
+use aoc2025::common::{Args, ParseError, read_input};
 use geo::{Covers, LineString, Polygon, Rect, coord};
 
 const DAY: u32 = 9;
+const MAX_COORD: i64 = 1_000_000_000;
+
+// Puzzle parameters, overridable with `--param key=value`. This puzzle has no
+// constants worth varying, so there are none, but typos are still caught.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Params {}
+
+impl Params {
+    fn from_args(args: &Args) -> Params {
+        args.expect_params(&[]);
+        Params {}
+    }
+}
 
 pub fn main() {
+    let params = Params::from_args(&Args::from_env());
     let input = day_input();
-    let part1_result = part1(&input);
+    let part1_result = part1(&input, &params);
     println!("Day {DAY}, part 1: {part1_result}");
-    let part2_result = part2(&input);
+    let part2_result = part2(&input, &params);
     println!("Day {DAY}, part 2: {part2_result}");
 }
 
 // Parse input
-fn process_input(input: Vec<String>) -> Vec<(i64, i64)> {
-    input
+pub fn process_input(input: Vec<String>) -> Result<Vec<(i64, i64)>, ParseError> {
+    let tiles = input
         .iter()
-        .map(|row| {
+        .enumerate()
+        .map(|(i, row)| {
             let splitted: Vec<&str> = row.split(",").collect();
-            let x = splitted[0].parse().unwrap();
-            let y = splitted[1].parse().unwrap();
-            (x, y)
+            if splitted.len() != 2 {
+                return Err(ParseError::at(i, format!("Expected x,y, got {row}")));
+            }
+            let parse = |s: &str| {
+                let c = s
+                    .parse::<i64>()
+                    .map_err(|e| ParseError::at(i, format!("Invalid coordinate {s}: {e}")))?;
+                // Keeps the rectangle areas within an i64
+                if c.abs() > MAX_COORD {
+                    return Err(ParseError::at(i, format!("Coordinate {c} is too large")));
+                }
+                Ok(c)
+            };
+            Ok((parse(splitted[0])?, parse(splitted[1])?))
         })
-        .collect()
+        .collect::<Result<Vec<_>, _>>()?;
+    if tiles.len() < 4 {
+        return Err(ParseError::at(
+            tiles.len(),
+            "The loop needs at least four red tiles",
+        ));
+    }
+    // Each red tile is connected to the next one by a straight line
+    for i in 0..tiles.len() {
+        let ((x1, y1), (x2, y2)) = (tiles[i], tiles[(i + 1) % tiles.len()]);
+        if x1 != x2 && y1 != y2 {
+            return Err(ParseError::at(
+                i,
+                "Not in the same row or column as the next tile",
+            ));
+        }
+    }
+    Ok(tiles)
 }
 
 // Parse sample input
@@ -37,15 +80,15 @@ fn _sample_input() -> Vec<(i64, i64)> {
     .split('\n')
     .map(|s| s.to_string())
     .collect();
-    process_input(data)
+    process_input(data).unwrap()
 }
 
 // Parse day's input
 fn day_input() -> Vec<(i64, i64)> {
-    process_input(read_input(DAY))
+    process_input(read_input(DAY)).unwrap_or_else(|e| panic!("Invalid input: {e}"))
 }
 
-fn part1(input: &Vec<(i64, i64)>) -> i64 {
+pub fn part1(input: &[(i64, i64)], _params: &Params) -> i64 {
     (0..input.len())
         .map(|i| {
             let (x1, y1) = input[i];
@@ -85,7 +128,7 @@ fn validate_area(polygon: &Polygon<f64>, corner1: (i64, i64), corner2: (i64, i64
 }
 
 // A bit slow (~6s on debug, ~instant on release)
-fn part2(input: &Vec<(i64, i64)>) -> i64 {
+pub fn part2(input: &[(i64, i64)], _params: &Params) -> i64 {
     let mut points = input
         .iter()
         .map(|(a, b)| coord! { x: *a as f64, y: *b as f64 })
@@ -123,28 +166,72 @@ fn part2(input: &Vec<(i64, i64)>) -> i64 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc2025::{
+        check,
+        generate::{self, Day09Size},
+        reference,
+    };
 
     #[test]
     fn test_part1() {
         let input = _sample_input();
-        assert_eq!(50, part1(&input));
+        assert_eq!(50, part1(&input, &Params::default()));
     }
 
     #[test]
     fn test_part2() {
         let input = _sample_input();
-        assert_eq!(24, part2(&input));
+        assert_eq!(24, part2(&input, &Params::default()));
+    }
+
+    #[test]
+    fn parse_errors() {
+        let lines = |data: &str| data.split('\n').map(|s| s.to_string()).collect();
+        assert!(process_input(lines("7")).is_err());
+        assert!(
+            process_input(lines(
+                "0,0\n0,3000000000\n3000000000,3000000000\n3000000000,0"
+            ))
+            .is_err()
+        );
+        assert!(process_input(lines("1,1\n1,5\n5,5\n6,1")).is_err());
+        assert!(process_input(lines("1,1\n1,5\n5,5")).is_err());
+    }
+
+    #[test]
+    fn differential() {
+        let size = Day09Size {
+            columns: 4,
+            max_coord: 20,
+        };
+        check::differential(
+            200,
+            |rng| generate::day09(rng, &size).unwrap(),
+            process_input,
+            |input| {
+                (
+                    part1(input, &Params::default()),
+                    part2(input, &Params::default()),
+                )
+            },
+            |input| {
+                (
+                    reference::day09::part1(input),
+                    reference::day09::part2(input),
+                )
+            },
+        );
     }
 
     #[test]
     fn real_part1() {
         let input = day_input();
-        assert_eq!(4748826374, part1(&input));
+        assert_eq!(4748826374, part1(&input, &Params::default()));
     }
 
     #[test]
     fn real_part2() {
         let input = day_input();
-        assert_eq!(1554370486, part2(&input));
+        assert_eq!(1554370486, part2(&input, &Params::default()));
     }
 }
@@ -1,69 +1,92 @@
-use aoc2025::common::read_input;
+use aoc2025::common::{Args, ParseError, read_input};
 use itertools::Itertools;
 use regex::Regex;
 use z3::{Optimize, ast::Int};
 
+// Parsed puzzle input
+pub type Input = Vec<(Vec<bool>, Vec<Vec<usize>>, Vec<usize>)>;
+
 const DAY: u32 = 10;
 
+// Puzzle parameters, overridable with `--param key=value`. This puzzle has no
+// constants worth varying, so there are none, but typos are still caught.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Params {}
+
+impl Params {
+    fn from_args(args: &Args) -> Params {
+        args.expect_params(&[]);
+        Params {}
+    }
+}
+
 pub fn main() {
+    let params = Params::from_args(&Args::from_env());
     let input = day_input();
-    let part1_result = part1(&input);
+    let part1_result = part1(&input, &params);
     println!("Day {DAY}, part 1: {part1_result}");
-    let part2_result = part2(&input);
+    let part2_result = part2(&input, &params);
     println!("Day {DAY}, part 2: {part2_result}");
 }
 
 // Parse input
-fn process_input(input: Vec<String>) -> Vec<(Vec<bool>, Vec<Vec<usize>>, Vec<usize>)> {
-    let pattern = Regex::new(r"\[([.#]+)\] (.+) \{(.+)\}").unwrap();
+pub fn process_input(input: Vec<String>) -> Result<Input, ParseError> {
+    let pattern = Regex::new(r"^\[([.#]+)\] (\(.+\)) \{([\d,]+)\}$").unwrap();
+    let buttons_pattern = Regex::new(r"^\([\d,]+\)( \([\d,]+\))*$").unwrap();
     let button_pattern = Regex::new(r"\(([\d,]+)\)").unwrap();
     input
         .iter()
-        .map(|row| {
-            let caps = pattern.captures(row).unwrap();
-            let grid = &caps[1]
-                .chars()
-                .map(|c| {
-                    if c == '#' {
-                        return true;
-                    }
-                    false
-                })
-                .collect::<Vec<bool>>();
+        .enumerate()
+        .map(|(i, row)| {
+            let caps = pattern
+                .captures(row)
+                .ok_or_else(|| ParseError::at(i, "Expected [lights] (buttons) {joltages}"))?;
+            let grid = caps[1].chars().map(|c| c == '#').collect::<Vec<bool>>();
+            let numbers = |s: &str| {
+                s.split(',')
+                    .map(|n| {
+                        n.parse::<usize>()
+                            .map_err(|e| ParseError::at(i, format!("Invalid number {n}: {e}")))
+                    })
+                    .collect::<Result<Vec<usize>, _>>()
+            };
             let buttons_str = &caps[2];
-            let joltages_str = &caps[3];
-            let buttons: Vec<Vec<usize>> = button_pattern
+            if !buttons_pattern.is_match(buttons_str) {
+                return Err(ParseError::at(i, format!("Invalid buttons {buttons_str}")));
+            }
+            let buttons = button_pattern
                 .captures_iter(buttons_str)
-                .map(|bcaps| {
-                    bcaps[1]
-                        .split(',')
-                        .map(|s| s.parse::<usize>().unwrap())
-                        .collect()
-                })
-                .collect();
-            let joltages: Vec<usize> = joltages_str
-                .split(',')
-                .map(|s| s.parse::<usize>().unwrap())
-                .collect();
-            (grid.clone(), buttons, joltages)
+                .map(|bcaps| numbers(&bcaps[1]))
+                .collect::<Result<Vec<Vec<usize>>, _>>()?;
+            if buttons.iter().flatten().any(|&light| light >= grid.len()) {
+                return Err(ParseError::at(
+                    i,
+                    "Button wired to a light that doesn't exist",
+                ));
+            }
+            let joltages = numbers(&caps[3])?;
+            if joltages.len() != grid.len() {
+                return Err(ParseError::at(i, "Expected a joltage for every light"));
+            }
+            Ok((grid, buttons, joltages))
         })
         .collect()
 }
 
 // Parse sample input
-fn _sample_input() -> Vec<(Vec<bool>, Vec<Vec<usize>>, Vec<usize>)> {
+fn _sample_input() -> Input {
     let data = "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
 [...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}
 [.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}"
         .split('\n')
         .map(|s| s.to_string())
         .collect();
-    process_input(data)
+    process_input(data).unwrap()
 }
 
 // Parse day's input
-fn day_input() -> Vec<(Vec<bool>, Vec<Vec<usize>>, Vec<usize>)> {
-    process_input(read_input(DAY))
+fn day_input() -> Input {
+    process_input(read_input(DAY)).unwrap_or_else(|e| panic!("Invalid input: {e}"))
 }
 
 fn fewest_presses(row: &(Vec<bool>, Vec<Vec<usize>>, Vec<usize>)) -> i64 {
@@ -85,12 +108,12 @@ fn fewest_presses(row: &(Vec<bool>, Vec<Vec<usize>>, Vec<usize>)) -> i64 {
         .unwrap_or(0) as i64
 }
 
-fn part1(input: &Vec<(Vec<bool>, Vec<Vec<usize>>, Vec<usize>)>) -> i64 {
+pub fn part1(input: &Input, _params: &Params) -> i64 {
     input.iter().map(|row| fewest_presses(row)).sum()
 }
 
-// Solve part2 for single row using Z3
-fn solve_row(buttons: &Vec<Vec<usize>>, joltages: &Vec<usize>) -> i64 {
+// Solve part2 for single row using Z3, None if the joltages can't be reached
+fn solve_row(buttons: &Vec<Vec<usize>>, joltages: &Vec<usize>) -> Option<i64> {
     let presses = (0..buttons.len())
         .map(|k| {
             let name = format!("presses_{}", k);
@@ -114,52 +137,96 @@ fn solve_row(buttons: &Vec<Vec<usize>>, joltages: &Vec<usize>) -> i64 {
             .filter_map(|(j, b)| if b.contains(&i) { Some(j) } else { None })
             .map(|i| &presses[i])
             .collect::<Vec<_>>();
+        if relevant_buttons.is_empty() {
+            if joltage != 0 {
+                return None;
+            }
+            continue;
+        }
         let sum_expr = Int::add(relevant_buttons.as_slice());
         solver.assert(&sum_expr.eq(joltage as i64));
     }
     if let z3::SatResult::Sat = solver.check(&[]) {
-        let model = solver.get_model().unwrap();
-        presses
-            .iter()
-            .map(|a| model.eval(a, true).unwrap().as_i64().unwrap())
-            .sum()
+        let model = solver.get_model()?;
+        presses.iter().map(|a| model.eval(a, true)?.as_i64()).sum()
     } else {
-        panic!("No solution found");
+        None
     }
 }
 
-fn part2(input: &Vec<(Vec<bool>, Vec<Vec<usize>>, Vec<usize>)>) -> i64 {
+pub fn part2(input: &Input, _params: &Params) -> i64 {
     input
         .iter()
-        .map(|row| solve_row(&row.1, &row.2))
+        // Like in part 1, machines without a solution don't count
+        .map(|row| solve_row(&row.1, &row.2).unwrap_or(0))
         .sum::<i64>()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc2025::{
+        check,
+        generate::{self, Day10Size},
+        reference,
+    };
 
     #[test]
     fn test_part1() {
         let input = _sample_input();
-        assert_eq!(7, part1(&input));
+        assert_eq!(7, part1(&input, &Params::default()));
     }
 
     #[test]
     fn test_part2() {
         let input = _sample_input();
-        assert_eq!(33, part2(&input));
+        assert_eq!(33, part2(&input, &Params::default()));
+    }
+
+    #[test]
+    fn parse_errors() {
+        let lines = |data: &str| data.split('\n').map(|s| s.to_string()).collect();
+        assert!(process_input(lines("[.#] (0,2) {1,1}")).is_err());
+        assert!(process_input(lines("[.#] (0,1) {1}")).is_err());
+        assert!(process_input(lines("[.#] () {1,1}")).is_err());
+    }
+
+    #[test]
+    fn differential() {
+        let size = Day10Size {
+            machines: 2,
+            max_lights: 4,
+            max_buttons: 5,
+            max_presses: 3,
+        };
+        check::differential(
+            50,
+            |rng| generate::day10(rng, &size).unwrap(),
+            process_input,
+            |input| {
+                (
+                    part1(input, &Params::default()),
+                    part2(input, &Params::default()),
+                )
+            },
+            |input| {
+                (
+                    reference::day10::part1(input),
+                    reference::day10::part2(input),
+                )
+            },
+        );
     }
 
     #[test]
     fn real_part1() {
         let input = day_input();
-        assert_eq!(444, part1(&input));
+        assert_eq!(444, part1(&input, &Params::default()));
     }
 
     #[test]
     fn real_part2() {
         let input = day_input();
-        assert_eq!(16513, part2(&input));
+        assert_eq!(16513, part2(&input, &Params::default()));
     }
 }
@@ -1,36 +1,170 @@
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
 
-use aoc2025::common::read_input;
+use aoc2025::common::{Args, ParseError, read_input};
+
+// Parsed puzzle input
+pub type Input = (Vec<String>, Vec<(String, String)>);
 
 const DAY: u32 = 11;
+// Most devices part 2 paths can be required to visit, as every device keeps a
+// count for each subset of them
+const MAX_VIA: usize = 8;
+
+// Puzzle parameters, overridable with `--param key=value`
+#[derive(Clone, Debug)]
+pub struct Params {
+    // Where part 1 paths start
+    start: String,
+    // Where part 2 paths start
+    server: String,
+    // Where all paths end
+    end: String,
+    // Devices every part 2 path has to visit, in any order
+    via: Vec<String>,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            start: "you".to_string(),
+            server: "svr".to_string(),
+            end: "out".to_string(),
+            via: vec!["dac".to_string(), "fft".to_string()],
+        }
+    }
+}
+
+impl Params {
+    fn from_args(args: &Args) -> Result<Params, String> {
+        args.expect_params(&["start", "server", "end", "via"]);
+        let default = Params::default();
+        // Devices to visit are given as a comma separated list, e.g. `via=dac,fft`
+        let via = match args.param("via", String::new()).as_str() {
+            "" => default.via,
+            list => list.split(',').map(|d| d.to_string()).collect(),
+        };
+        Params {
+            start: args.param("start", default.start),
+            server: args.param("server", default.server),
+            end: args.param("end", default.end),
+            via,
+        }
+        .checked()
+    }
+
+    // The parameters, if the devices to visit are distinct, few enough to count
+    // and not the end device
+    fn checked(self) -> Result<Params, String> {
+        if self.via.len() > MAX_VIA {
+            return Err(format!(
+                "At most {MAX_VIA} devices can be visited, got {}",
+                self.via.len()
+            ));
+        }
+        for (i, device) in self.via.iter().enumerate() {
+            if self.via[..i].contains(device) {
+                return Err(format!("Device {device} is visited twice"));
+            }
+            if *device == self.end {
+                return Err(format!(
+                    "Paths end at {device}, it can't be visited on the way"
+                ));
+            }
+        }
+        Ok(self)
+    }
+}
+
+// Device with more paths to the end device than fit in an i64
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TooManyPaths(pub String);
+
+impl fmt::Display for TooManyPaths {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Too many paths to count from {}", self.0)
+    }
+}
 
 pub fn main() {
+    let params =
+        Params::from_args(&Args::from_env()).unwrap_or_else(|e| panic!("Invalid parameters: {e}"));
     let input = day_input();
-    let part1_result = part1(&input);
-    println!("Day {DAY}, part 1: {part1_result}");
-    let part2_result = part2(&input);
-    println!("Day {DAY}, part 2: {part2_result}");
+    match part1(&input, &params) {
+        Ok(result) => println!("Day {DAY}, part 1: {result}"),
+        Err(e) => println!("Day {DAY}, part 1 failed: {e}"),
+    }
+    match part2(&input, &params) {
+        Ok(result) => println!("Day {DAY}, part 2: {result}"),
+        Err(e) => println!("Day {DAY}, part 2 failed: {e}"),
+    }
 }
 
 // Parse input
-fn process_input(input: Vec<String>) -> (Vec<String>, Vec<(String, String)>) {
+pub fn process_input(input: Vec<String>) -> Result<Input, ParseError> {
     let mut nodes = vec![];
     let mut edges = vec![];
-    input.iter().for_each(|row| {
-        let parts = row.split(": ").collect::<Vec<&str>>();
-        let name = parts[0].to_string();
-        let new_edges = parts[1]
+    for (i, row) in input.iter().enumerate() {
+        let (name, outputs) = row
+            .split_once(": ")
+            .ok_or_else(|| ParseError::at(i, format!("Expected device: outputs, got {row}")))?;
+        if name.is_empty() || outputs.split(" ").any(|s| s.is_empty()) {
+            return Err(ParseError::at(i, "Empty device name"));
+        }
+        let new_edges = outputs
             .split(" ")
-            .map(|s| (name.clone(), s.to_string()))
+            .map(|s| (name.to_string(), s.to_string()))
             .collect::<Vec<(String, String)>>();
-        nodes.push(name);
+        nodes.push(name.to_string());
         edges.extend(new_edges);
-    });
-    (nodes, edges)
+    }
+    if let Some(device) = find_cycle(&edges) {
+        let line = nodes.iter().position(|n| *n == device).unwrap();
+        return Err(ParseError::at(
+            line,
+            format!("Device {device} is part of a loop"),
+        ));
+    }
+    Ok((nodes, edges))
+}
+
+// Device on a cycle, if any. Path counting would never finish on those.
+fn find_cycle(edges: &[(String, String)]) -> Option<String> {
+    let mut outputs: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (from, to) in edges {
+        outputs.entry(from).or_default().push(to);
+    }
+    // Depth-first search, where a device still on the stack is visited again on a loop
+    let mut finished: HashSet<&str> = HashSet::new();
+    for &start in outputs.keys() {
+        let mut on_stack = HashSet::from([start]);
+        let mut stack = vec![(start, 0)];
+        while let Some((device, next)) = stack.pop() {
+            match outputs.get(device).and_then(|o| o.get(next)) {
+                Some(&output) => {
+                    stack.push((device, next + 1));
+                    if on_stack.contains(output) {
+                        return Some(output.to_string());
+                    }
+                    if finished.insert(output) {
+                        on_stack.insert(output);
+                        stack.push((output, 0));
+                    }
+                }
+                None => {
+                    on_stack.remove(device);
+                    finished.insert(device);
+                }
+            }
+        }
+    }
+    None
 }
 
 // Parse sample input
-fn _sample_input() -> (Vec<String>, Vec<(String, String)>) {
+fn _sample_input() -> Input {
     let data = "aaa: you hhh
 you: bbb ccc
 bbb: ddd eee
@@ -44,10 +178,10 @@ iii: out"
         .split('\n')
         .map(|s| s.to_string())
         .collect();
-    process_input(data)
+    process_input(data).unwrap()
 }
 
-fn _sample_input2() -> (Vec<String>, Vec<(String, String)>) {
+fn _sample_input2() -> Input {
     let data = "svr: aaa bbb
 aaa: fft
 fft: ccc
@@ -64,117 +198,206 @@ hhh: out"
         .split('\n')
         .map(|s| s.to_string())
         .collect();
-    process_input(data)
+    process_input(data).unwrap()
 }
 
 // Parse day's input
-fn day_input() -> (Vec<String>, Vec<(String, String)>) {
-    process_input(read_input(DAY))
+fn day_input() -> Input {
+    process_input(read_input(DAY)).unwrap_or_else(|e| panic!("Invalid input: {e}"))
 }
 
-fn part1(input: &(Vec<String>, Vec<(String, String)>)) -> i64 {
+pub fn part1(input: &Input, params: &Params) -> Result<i64, TooManyPaths> {
     let (_, edges) = input;
     let mut steps: HashMap<String, i64> = HashMap::new();
 
     fn recursive(
         current: String,
-        edges: &Vec<(String, String)>,
+        end: &str,
+        edges: &[(String, String)],
         steps: &mut HashMap<String, i64>,
-    ) -> i64 {
-        if current == "out" {
-            return 1;
+    ) -> Result<i64, TooManyPaths> {
+        if current == end {
+            return Ok(1);
         }
         if let Some(&s) = steps.get(&current) {
-            return s;
+            return Ok(s);
         }
-        let result = edges
-            .iter()
-            .filter_map(|(from, to)| {
-                if from == &current {
-                    Some(recursive(to.clone(), &edges, steps))
-                } else {
-                    None
-                }
-            })
-            .sum();
+        let result =
+            edges
+                .iter()
+                .filter(|(from, _)| from == &current)
+                .try_fold(0i64, |sum, (_, to)| {
+                    let paths = recursive(to.clone(), end, edges, steps)?;
+                    sum.checked_add(paths)
+                        .ok_or_else(|| TooManyPaths(current.clone()))
+                })?;
         //println!("At {:?}, found {} paths", current, result);
         steps.insert(current, result);
-        result
+        Ok(result)
     }
 
-    recursive("you".to_string(), edges, &mut steps)
+    recursive(params.start.clone(), &params.end, edges, &mut steps)
 }
 
-fn part2(input: &(Vec<String>, Vec<(String, String)>)) -> i64 {
+pub fn part2(input: &Input, params: &Params) -> Result<i64, TooManyPaths> {
     let (_, edges) = input;
-    let mut steps: HashMap<String, (i64, i64, i64, i64)> = HashMap::new();
-    // (total paths, paths with dac, paths with fft, paths with both)
+    // Paths that visit at least the devices of `via` in each subset, with
+    // device i in the subsets that have bit i set
+    let mut steps: HashMap<String, Vec<i64>> = HashMap::new();
 
     fn recursive(
         current: String,
-        edges: &Vec<(String, String)>,
-        steps: &mut HashMap<String, (i64, i64, i64, i64)>,
-    ) -> (i64, i64, i64, i64) {
-        if current == "out" {
-            return (1, 0, 0, 0);
+        params: &Params,
+        edges: &[(String, String)],
+        steps: &mut HashMap<String, Vec<i64>>,
+    ) -> Result<Vec<i64>, TooManyPaths> {
+        let subsets = 1 << params.via.len();
+        if current == params.end {
+            let mut paths = vec![0; subsets];
+            paths[0] = 1;
+            return Ok(paths);
         }
-        if let Some(&s) = steps.get(&current) {
-            return s;
+        if let Some(s) = steps.get(&current) {
+            return Ok(s.clone());
+        }
+        let mut result = vec![0i64; subsets];
+        for (_, to) in edges.iter().filter(|(from, _)| from == &current) {
+            let paths = recursive(to.clone(), params, edges, steps)?;
+            for (sum, p) in result.iter_mut().zip(paths) {
+                *sum = sum
+                    .checked_add(p)
+                    .ok_or_else(|| TooManyPaths(current.clone()))?;
+            }
+        }
+        // Paths through this device visit it whether the subset asks for it or not
+        if let Some(i) = params.via.iter().position(|d| *d == current) {
+            result = (0..subsets)
+                .map(|subset| result[subset & !(1 << i)])
+                .collect();
         }
-        let result = edges
-            .iter()
-            .filter_map(|(from, to)| {
-                if from == &current {
-                    Some(recursive(to.clone(), &edges, steps))
-                } else {
-                    None
-                }
-            })
-            .reduce(|a, b| (a.0 + b.0, a.1 + b.1, a.2 + b.2, a.3 + b.3))
-            .map(|(total, dac, fft, both)| {
-                if current == "dac" {
-                    (total, total, fft, fft)
-                } else if current == "fft" {
-                    (total, dac, total, dac)
-                } else {
-                    (total, dac, fft, both)
-                }
-            })
-            .unwrap_or((0, 0, 0, 0));
 
         //println!("At {:?}, found {:?}", current, result);
-        steps.insert(current, result);
-        result
+        steps.insert(current, result.clone());
+        Ok(result)
     }
 
-    recursive("svr".to_string(), edges, &mut steps).3
+    let paths = recursive(params.server.clone(), params, edges, &mut steps)?;
+    Ok(paths[paths.len() - 1])
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc2025::{
+        check,
+        generate::{self, Day11Size},
+        reference,
+    };
 
     #[test]
     fn test_part1() {
         let input = _sample_input();
-        assert_eq!(5, part1(&input));
+        assert_eq!(Ok(5), part1(&input, &Params::default()));
     }
 
     #[test]
     fn test_part2() {
         let input = _sample_input2();
-        assert_eq!(2, part2(&input));
+        assert_eq!(Ok(2), part2(&input, &Params::default()));
+    }
+
+    #[test]
+    fn test_params() {
+        let input = _sample_input2();
+        let via = |devices: &[&str]| Params {
+            via: devices.iter().map(|d| d.to_string()).collect(),
+            ..Params::default()
+        };
+        // Every path goes through ccc, and half of them through eee and dac
+        assert_eq!(Ok(4), part2(&input, &via(&["ccc", "eee", "dac"])));
+        assert_eq!(Ok(8), part2(&input, &via(&["ccc"])));
+        assert_eq!(Ok(8), part2(&input, &via(&[])));
+        let from_aaa = Params {
+            start: "aaa".to_string(),
+            ..Params::default()
+        };
+        assert_eq!(Ok(4), part1(&input, &from_aaa));
+        assert!(via(&["dac", "fft", "dac"]).checked().is_err());
+        assert!(via(&["out"]).checked().is_err());
+        assert!(via(&["a"; MAX_VIA + 1]).checked().is_err());
+    }
+
+    #[test]
+    fn test_too_many_paths() {
+        // 70 layers of two devices, each connected to both devices of the
+        // next layer, doubling the paths
+        let layered = |start: &str| {
+            let mut lines = vec![format!("{start}: a0 b0")];
+            for i in 0..70 {
+                let next = if i == 69 {
+                    "out".to_string()
+                } else {
+                    format!("a{} b{}", i + 1, i + 1)
+                };
+                lines.push(format!("a{i}: {next}"));
+                lines.push(format!("b{i}: {next}"));
+            }
+            process_input(lines).unwrap()
+        };
+        assert_eq!(
+            Err(TooManyPaths("a6".to_string())),
+            part1(&layered("you"), &Params::default())
+        );
+        assert_eq!(
+            Err(TooManyPaths("a6".to_string())),
+            part2(&layered("svr"), &Params::default())
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        let lines = |data: &str| data.split('\n').map(|s| s.to_string()).collect();
+        assert!(process_input(lines("aaa")).is_err());
+        assert!(process_input(lines("aaa: bbb\nbbb: ccc\nccc: aaa")).is_err());
+        assert!(process_input(lines("aaa: aaa")).is_err());
+        assert!(process_input(lines("aaa: ")).is_err());
+    }
+
+    #[test]
+    fn differential() {
+        let size = Day11Size {
+            devices: 24,
+            layers: 6,
+            max_outputs: 3,
+        };
+        check::differential(
+            200,
+            |rng| generate::day11(rng, &size).unwrap(),
+            process_input,
+            |input| {
+                (
+                    part1(input, &Params::default()).unwrap(),
+                    part2(input, &Params::default()).unwrap(),
+                )
+            },
+            |(_, edges)| {
+                (
+                    reference::day11::part1(edges),
+                    reference::day11::part2(edges),
+                )
+            },
+        );
     }
 
     #[test]
     fn real_part1() {
         let input = day_input();
-        assert_eq!(634, part1(&input));
+        assert_eq!(Ok(634), part1(&input, &Params::default()));
     }
 
     #[test]
     fn real_part2() {
         let input = day_input();
-        assert_eq!(377452269415704, part2(&input));
+        assert_eq!(Ok(377452269415704), part2(&input, &Params::default()));
     }
 }
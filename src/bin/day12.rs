@@ -1,40 +1,129 @@
-use aoc2025::common::read_input;
+use std::fmt;
+
+use aoc2025::common::{Args, ParseError, read_input};
 use regex::Regex;
 
+// Parsed puzzle input
+pub type Input = (Vec<Vec<Vec<char>>>, Vec<(usize, usize, Vec<usize>)>);
+
 const DAY: u32 = 12;
 
+// Puzzle parameters, overridable with `--param key=value`
+#[derive(Clone, Copy, Debug)]
+pub struct Params {
+    // Cells every present covers
+    cells: u64,
+    // Side of the square every present fits in
+    side: u64,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params { cells: 7, side: 3 }
+    }
+}
+
+impl Params {
+    fn from_args(args: &Args) -> Result<Params, String> {
+        args.expect_params(&["cells", "side"]);
+        let default = Params::default();
+        Params {
+            cells: args.param("cells", default.cells),
+            side: args.param("side", default.side),
+        }
+        .checked()
+    }
+
+    // The parameters, if presents have cells and fit in their square
+    fn checked(self) -> Result<Params, String> {
+        if self.side == 0 {
+            return Err("Presents need a side of at least 1".to_string());
+        }
+        if self.cells == 0 || self.cells as u128 > self.side as u128 * self.side as u128 {
+            return Err(format!(
+                "Presents in a {0}x{0} square cover 1 to {1} cells, got {2}",
+                self.side,
+                self.side as u128 * self.side as u128,
+                self.cells
+            ));
+        }
+        Ok(self)
+    }
+}
+
+// Region the quick checks can't decide, which would need an exact packing
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Undecided {
+    pub region: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl fmt::Display for Undecided {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Region {} ({}x{}) needs an exact packing, which isn't supported",
+            self.region + 1,
+            self.width,
+            self.height
+        )
+    }
+}
+
 pub fn main() {
+    let params =
+        Params::from_args(&Args::from_env()).unwrap_or_else(|e| panic!("Invalid parameters: {e}"));
     let input = day_input();
-    let part1_result = part1(&input);
-    println!("Day {DAY}, part 1: {part1_result}");
+    match part1(&input, &params) {
+        Ok(result) => println!("Day {DAY}, part 1: {result}"),
+        Err(e) => println!("Day {DAY}, part 1 failed: {e}"),
+    }
 }
 
 // Parse input
-fn process_input(input: Vec<String>) -> (Vec<Vec<Vec<char>>>, Vec<(usize, usize, Vec<usize>)>) {
-    let area_pattern = Regex::new(r"^(\d+)x(\d+): ([\d ]+)$").unwrap();
+pub fn process_input(input: Vec<String>) -> Result<Input, ParseError> {
+    let area_pattern = Regex::new(r"^(\d+)x(\d+): (\d+(?: \d+)*)$").unwrap();
+    let header_pattern = Regex::new(r"^\d+:$").unwrap();
     let mut shapes = vec![];
     let mut areas = vec![];
     let mut current_shape = vec![];
-    input.iter().for_each(|row| {
+    for (i, row) in input.iter().enumerate() {
+        let number = |s: &str| {
+            s.parse::<usize>()
+                .map_err(|e| ParseError::at(i, format!("Invalid number {s}: {e}")))
+        };
         if let Some(caps) = area_pattern.captures(row) {
-            let width: usize = caps[1].parse().unwrap();
-            let height: usize = caps[2].parse().unwrap();
-            let shape_indices: Vec<usize> =
-                caps[3].split(' ').map(|s| s.parse().unwrap()).collect();
+            let width = number(&caps[1])?;
+            let height = number(&caps[2])?;
+            let shape_indices = caps[3]
+                .split(' ')
+                .map(number)
+                .collect::<Result<Vec<_>, _>>()?;
+            // Shapes all come before the regions
+            if shape_indices.len() > shapes.len() {
+                return Err(ParseError::at(i, "More shape counts than shapes"));
+            }
             areas.push((width, height, shape_indices));
         } else if row.is_empty() {
             shapes.push(current_shape.clone());
             current_shape.clear();
-        } else if !row.contains(":") {
+        } else if header_pattern.is_match(row) {
             // Ignore headers
+        } else if row.chars().all(|c| c == '#' || c == '.') {
             current_shape.push(row.chars().collect::<Vec<char>>());
+        } else {
+            return Err(ParseError::at(
+                i,
+                format!("Expected shape or region, got {row}"),
+            ));
         }
-    });
-    (shapes, areas)
+    }
+    Ok((shapes, areas))
 }
 
 // Parse sample input
-fn _sample_input() -> (Vec<Vec<Vec<char>>>, Vec<(usize, usize, Vec<usize>)>) {
+fn _sample_input() -> Input {
     let data = "0:
 ###
 ##.
@@ -71,50 +160,149 @@ fn _sample_input() -> (Vec<Vec<Vec<char>>>, Vec<(usize, usize, Vec<usize>)>) {
         .split('\n')
         .map(|s| s.to_string())
         .collect();
-    process_input(data)
+    process_input(data).unwrap()
 }
 
 // Parse day's input
-fn day_input() -> (Vec<Vec<Vec<char>>>, Vec<(usize, usize, Vec<usize>)>) {
-    process_input(read_input(DAY))
+fn day_input() -> Input {
+    process_input(read_input(DAY)).unwrap_or_else(|e| panic!("Invalid input: {e}"))
 }
 
-fn part1(input: &(Vec<Vec<Vec<char>>>, Vec<(usize, usize, Vec<usize>)>)) -> i64 {
+pub fn part1(input: &Input, params: &Params) -> Result<i64, Undecided> {
     let (_, areas) = input;
-    areas
-        .iter()
-        .filter(|area| {
-            let (width, height, shape_indices) = area;
-            let remaining = shape_indices
-                .iter()
-                .enumerate()
-                .flat_map(|(i, val)| vec![i; *val])
-                .collect::<Vec<_>>();
-            if remaining.len() * 7 > width * height {
-                return false;
-            }
+    let mut count = 0;
+    for (region, (width, height, shape_indices)) in areas.iter().enumerate() {
+        // Counted in u128, where neither the sum of the counts nor the areas
+        // can overflow, but the cells of all presents still can
+        let remaining = shape_indices.iter().map(|&c| c as u128).sum::<u128>();
+        let cells = remaining.checked_mul(params.cells as u128);
+        if cells.is_none_or(|cells| cells > *width as u128 * *height as u128) {
+            continue;
+        }
 
-            let rows = height / 3;
-            let cols = width / 3;
-            let grid_total = rows * cols;
-            if remaining.len() <= grid_total {
-                // Easy fit
-                true
-            } else {
-                // More precise
-                panic!("Too difficult input :)")
-            }
-        })
-        .count() as i64
+        let rows = *height as u128 / params.side as u128;
+        let cols = *width as u128 / params.side as u128;
+        let grid_total = rows * cols;
+        if remaining <= grid_total {
+            // Easy fit
+            count += 1;
+        } else {
+            // More precise
+            return Err(Undecided {
+                region,
+                width: *width,
+                height: *height,
+            });
+        }
+    }
+    Ok(count)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc2025::{
+        check,
+        generate::{self, Day12Size},
+        reference,
+    };
+
+    #[test]
+    fn test_undecided() {
+        let input = _sample_input();
+        assert_eq!(
+            Err(Undecided {
+                region: 0,
+                width: 4,
+                height: 4
+            }),
+            part1(&input, &Params::default())
+        );
+        assert_eq!(
+            "Region 1 (4x4) needs an exact packing, which isn't supported",
+            part1(&input, &Params::default()).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn test_large_regions() {
+        let lines = |data: &str| data.split('\n').map(|s| s.to_string()).collect();
+        // Counts and areas that used to overflow
+        let huge = process_input(lines(
+            "0:\n#\n\n18446744073709551615x18446744073709551615: 1",
+        ))
+        .unwrap();
+        assert_eq!(Ok(1), part1(&huge, &Params::default()));
+        let crowded =
+            process_input(lines("0:\n#\n\n1:\n#\n\n4x4: 18446744073709551615 1")).unwrap();
+        assert_eq!(Ok(0), part1(&crowded, &Params::default()));
+    }
+
+    #[test]
+    fn test_params() {
+        let input = _sample_input();
+        // Presents of a single cell fit anywhere there are enough cells
+        let single = Params { cells: 1, side: 1 };
+        assert_eq!(Ok(3), part1(&input, &single));
+        // Presents too large for every region
+        let large = Params { cells: 25, side: 5 };
+        assert_eq!(Ok(0), part1(&input, &large));
+        let lines = |data: &str| data.split('\n').map(|s| s.to_string()).collect();
+        let crowded = process_input(lines(
+            "0:\n#\n\n1:\n#\n\n4x4: 18446744073709551615 18446744073709551615",
+        ))
+        .unwrap();
+        let huge = Params {
+            cells: u64::MAX,
+            side: u64::MAX,
+        };
+        assert_eq!(Ok(0), part1(&crowded, &huge));
+        assert!(Params { cells: 1, side: 0 }.checked().is_err());
+        assert!(Params { cells: 0, side: 3 }.checked().is_err());
+        assert!(Params { cells: 10, side: 3 }.checked().is_err());
+        assert!(huge.checked().is_ok());
+    }
+
+    #[test]
+    fn parse_errors() {
+        let lines = |data: &str| data.split('\n').map(|s| s.to_string()).collect();
+        assert!(process_input(lines("0:\n#\n\n4x4: 1 1")).is_err());
+        assert!(process_input(lines("0:\n#x\n")).is_err());
+        assert!(process_input(lines("0:\n#\n\n4x4: 99999999999999999999")).is_err());
+    }
+
+    #[test]
+    fn differential() {
+        let size = Day12Size {
+            shapes: 6,
+            regions: 4,
+            max_side: 8,
+        };
+        check::differential(
+            100,
+            |rng| generate::day12(rng, &size).unwrap(),
+            process_input,
+            |input| part1(input, &Params::default()).unwrap(),
+            |(shapes, regions)| reference::day12::part1(shapes, regions),
+        );
+
+        // Regions need room for at least one present, and shapes to pick from
+        let mut rng = generate::Rng::new(1);
+        let small = Day12Size {
+            max_side: 2,
+            ..size.clone()
+        };
+        assert_eq!(
+            Err("max_side must be at least 3, got 2".to_string()),
+            generate::day12(&mut rng, &small)
+        );
+        let shapeless = Day12Size { shapes: 0, ..size };
+        assert!(generate::day12(&mut rng, &shapeless).is_err());
+    }
 
     #[test]
     fn real_part1() {
         let input = day_input();
-        assert_eq!(479, part1(&input));
+        assert_eq!(Ok(479), part1(&input, &Params::default()));
     }
 }
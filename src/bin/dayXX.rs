@@ -1,4 +1,4 @@
-use aoc2025::common::read_input;
+use aoc2025::common::{ParseError, read_input};
 
 const DAY: u32 = 3;
 
@@ -11,8 +11,8 @@ pub fn main() {
 }
 
 // Parse input
-fn process_input(input: Vec<String>) -> Vec<String> {
-    input
+pub fn process_input(input: Vec<String>) -> Result<Vec<String>, ParseError> {
+    Ok(input)
 }
 
 // Parse sample input
@@ -26,19 +26,19 @@ fn _sample_input() -> Vec<String> {
         .split('\n')
         .map(|s| s.to_string())
         .collect();
-    process_input(data)
+    process_input(data).unwrap()
 }
 
 // Parse day's input
 fn day_input() -> Vec<String> {
-    process_input(read_input(DAY))
+    process_input(read_input(DAY)).unwrap_or_else(|e| panic!("Invalid input: {e}"))
 }
 
-fn part1(_input: &Vec<String>) -> i64 {
+pub fn part1(_input: &Vec<String>) -> i64 {
     0
 }
 
-fn part2(_input: &Vec<String>) -> i64 {
+pub fn part2(_input: &Vec<String>) -> i64 {
     0
 }
 
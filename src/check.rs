@@ -0,0 +1,134 @@
+use std::{
+    cell::Cell,
+    fmt::Debug,
+    panic::{self, AssertUnwindSafe},
+    sync::Once,
+};
+
+use crate::{common::ParseError, generate::Rng};
+
+thread_local! {
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+}
+
+// Run `f`, catching a panic without printing its message. Other threads keep
+// their normal panic output, so failing tests still report properly.
+fn quietly<T>(f: impl FnOnce() -> T) -> Option<T> {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let default = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !QUIET.with(|q| q.get()) {
+                default(info);
+            }
+        }));
+    });
+    QUIET.with(|q| q.set(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(f)).ok();
+    QUIET.with(|q| q.set(false));
+    result
+}
+
+// Compare a fast solver with a reference solver on `cases` generated inputs.
+// A mismatch (or a panic in either solver) is shrunk to a small input that
+// still disagrees, and reported as raw puzzle lines.
+pub fn differential<I, T: PartialEq + Debug>(
+    cases: u64,
+    generate: impl Fn(&mut Rng) -> Vec<String>,
+    parse: impl Fn(Vec<String>) -> Result<I, ParseError>,
+    fast: impl Fn(&I) -> T,
+    reference: impl Fn(&I) -> T,
+) {
+    // Returns a description of the disagreement, if there is one
+    let disagreement = |lines: &[String]| -> Option<String> {
+        // Shrunk candidates may not be valid puzzle inputs at all
+        let input = parse(lines.to_vec()).ok()?;
+        let fast = quietly(|| fast(&input));
+        let reference = quietly(|| reference(&input));
+        match (fast, reference) {
+            (Some(a), Some(b)) if a == b => None,
+            (Some(a), Some(b)) => Some(format!("fast {a:?}, reference {b:?}")),
+            (None, _) => Some("fast solver panicked".to_string()),
+            (_, None) => Some("reference solver panicked".to_string()),
+        }
+    };
+
+    for seed in 0..cases {
+        let lines = generate(&mut Rng::new(seed));
+        if disagreement(&lines).is_some() {
+            let shrunk = shrink(lines, |l| disagreement(l).is_some());
+            panic!(
+                "Counterexample from seed {seed}: {}\n{}",
+                disagreement(&shrunk).unwrap(),
+                shrunk.join("\n")
+            );
+        }
+    }
+}
+
+// Greedily apply the first simplification that keeps the input failing,
+// until none of them do
+pub fn shrink(lines: Vec<String>, fails: impl Fn(&[String]) -> bool) -> Vec<String> {
+    let mut current = lines;
+    loop {
+        let smaller = candidates(&current).find(|c| fails(c));
+        match smaller {
+            Some(smaller) => current = smaller,
+            None => return current,
+        }
+    }
+}
+
+// Simplifications of an input: drop a line, drop a comma or space separated
+// item from a line, or halve a number
+fn candidates(lines: &[String]) -> impl Iterator<Item = Vec<String>> + '_ {
+    let removals = (0..lines.len()).map(move |i| {
+        let mut c = lines.to_vec();
+        c.remove(i);
+        c
+    });
+    let items = (0..lines.len()).flat_map(move |i| {
+        [',', ' '].into_iter().flat_map(move |sep| {
+            let parts = lines[i].split(sep).collect::<Vec<_>>();
+            let count = if parts.len() > 1 { parts.len() } else { 0 };
+            (0..count).map(move |j| {
+                let mut parts = parts.clone();
+                parts.remove(j);
+                let mut c = lines.to_vec();
+                c[i] = parts.join(&sep.to_string());
+                c
+            })
+        })
+    });
+    let numbers = (0..lines.len()).flat_map(move |i| {
+        number_spans(&lines[i])
+            .into_iter()
+            .filter_map(move |(start, end)| {
+                let value: u64 = lines[i][start..end].parse().ok()?;
+                if value == 0 {
+                    return None;
+                }
+                let mut c = lines.to_vec();
+                c[i] = format!("{}{}{}", &lines[i][..start], value / 2, &lines[i][end..]);
+                Some(c)
+            })
+    });
+    removals.chain(items).chain(numbers)
+}
+
+// Byte ranges of the digit runs in a line
+fn number_spans(line: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (i, c) in line.char_indices().chain([(line.len(), ' ')]) {
+        match (c.is_ascii_digit(), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                spans.push((s, i));
+                start = None;
+            }
+            _ => (),
+        }
+    }
+    spans
+}
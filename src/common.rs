@@ -1,6 +1,11 @@
 use std::{
+    collections::HashMap,
+    env,
+    error::Error,
+    fmt::{self, Debug, Display},
     fs::File,
     io::{BufRead, BufReader},
+    str::FromStr,
 };
 
 pub fn read_input(day: u32) -> Vec<String> {
@@ -9,3 +14,126 @@ pub fn read_input(day: u32) -> Vec<String> {
     let reader = BufReader::new(file);
     reader.lines().map(|l| l.unwrap()).collect()
 }
+
+// Malformed puzzle input, with the (1-based) line it was found on
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    // Error for the line at the given 0-based index
+    pub fn at(index: usize, message: impl Into<String>) -> ParseError {
+        ParseError {
+            line: index + 1,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for ParseError {}
+
+// Command line arguments: positional values, `--flag`, `--name=value` and `--param key=value`
+pub struct Args {
+    positional: Vec<String>,
+    options: HashMap<String, Option<String>>,
+    params: HashMap<String, String>,
+}
+
+impl Args {
+    // Read arguments of the running binary
+    pub fn from_env() -> Args {
+        Args::parse(env::args().skip(1))
+    }
+
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Args {
+        let mut positional = Vec::new();
+        let mut options = HashMap::new();
+        let mut params = HashMap::new();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if arg == "--param" {
+                let pair = args.next().expect("Missing value for --param");
+                let (key, value) = pair
+                    .split_once('=')
+                    .unwrap_or_else(|| panic!("Expected key=value, got {pair}"));
+                params.insert(key.to_string(), value.to_string());
+            } else if let Some(option) = arg.strip_prefix("--") {
+                match option.split_once('=') {
+                    Some((name, value)) => {
+                        options.insert(name.to_string(), Some(value.to_string()))
+                    }
+                    None => options.insert(option.to_string(), None),
+                };
+            } else {
+                positional.push(arg);
+            }
+        }
+        Args {
+            positional,
+            options,
+            params,
+        }
+    }
+
+    pub fn positional(&self) -> &[String] {
+        &self.positional
+    }
+
+    // Panic on options the binary doesn't know about, so typos don't go unnoticed
+    pub fn expect_options(&self, known: &[&str]) {
+        for name in self.options.keys() {
+            if !known.contains(&name.as_str()) {
+                panic!("Unknown option --{name}, expected one of {known:?}");
+            }
+        }
+    }
+
+    // Whether `--name` or `--name=value` was given
+    pub fn flag(&self, name: &str) -> bool {
+        self.options.contains_key(name)
+    }
+
+    // Value of `--name=value`, if given
+    pub fn option(&self, name: &str) -> Option<&str> {
+        self.options.get(name).and_then(|v| v.as_deref())
+    }
+
+    // Value of `--name=value`, if given, for options that do nothing without one
+    pub fn value(&self, name: &str) -> Option<&str> {
+        match self.options.get(name) {
+            Some(Some(value)) => Some(value),
+            Some(None) => panic!("Missing value for --{name}, expected --{name}=<value>"),
+            None => None,
+        }
+    }
+
+    // Panic on parameters the day doesn't know about, so typos don't go unnoticed
+    pub fn expect_params(&self, known: &[&str]) {
+        for key in self.params.keys() {
+            if !known.contains(&key.as_str()) {
+                panic!("Unknown parameter {key}, expected one of {known:?}");
+            }
+        }
+    }
+
+    // Value of a parameter, or the given default if it wasn't overridden
+    pub fn param<T: FromStr>(&self, key: &str, default: T) -> T
+    where
+        T::Err: Debug,
+    {
+        match self.params.get(key) {
+            Some(value) => value
+                .parse()
+                .unwrap_or_else(|e| panic!("Invalid value {value} for {key}: {e:?}")),
+            None => default,
+        }
+    }
+}
@@ -0,0 +1,823 @@
+use std::collections::HashSet;
+
+use crate::common::Args;
+
+// Small seeded random number generator (SplitMix64), so generated inputs are
+// reproducible without depending on an external crate's stream stability
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    // Uniform value in the inclusive range low..=high
+    pub fn range(&mut self, low: i64, high: i64) -> i64 {
+        assert!(low <= high, "Empty range {low}..={high}");
+        let span = (high as i128 - low as i128 + 1) as u128;
+        (low as i128 + (self.next_u64() as u128 % span) as i128) as i64
+    }
+
+    pub fn index(&mut self, len: usize) -> usize {
+        self.range(0, len as i64 - 1) as usize
+    }
+
+    // True with the given probability
+    pub fn chance(&mut self, probability: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < probability
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.index(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
+
+// Size parameters a generator can't work with are reported instead of panicking
+fn at_least(name: &str, value: i128, min: i128) -> Result<(), String> {
+    if value < min {
+        return Err(format!("{name} must be at least {min}, got {value}"));
+    }
+    Ok(())
+}
+
+fn at_most(name: &str, value: i128, max: i128) -> Result<(), String> {
+    if value > max {
+        return Err(format!("{name} must be at most {max}, got {value}"));
+    }
+    Ok(())
+}
+
+// Random number with exactly `digits` decimal digits, none of them zero
+fn nonzero_digits(rng: &mut Rng, digits: usize) -> String {
+    (0..digits)
+        .map(|_| char::from(b'0' + rng.range(1, 9) as u8))
+        .collect()
+}
+
+// Dial rotations like `L68`
+#[derive(Clone, Debug)]
+pub struct Day01Size {
+    pub rotations: usize,
+    pub max_distance: i64,
+}
+
+impl Default for Day01Size {
+    fn default() -> Self {
+        Day01Size {
+            rotations: 4000,
+            max_distance: 1000,
+        }
+    }
+}
+
+impl Day01Size {
+    pub fn from_args(args: &Args) -> Self {
+        args.expect_params(&["rotations", "max_distance"]);
+        let default = Self::default();
+        Day01Size {
+            rotations: args.param("rotations", default.rotations),
+            max_distance: args.param("max_distance", default.max_distance),
+        }
+    }
+}
+
+pub fn day01(rng: &mut Rng, size: &Day01Size) -> Result<Vec<String>, String> {
+    at_least("max_distance", size.max_distance as i128, 1)?;
+    Ok((0..size.rotations)
+        .map(|_| {
+            let dir = if rng.chance(0.5) { 'L' } else { 'R' };
+            format!("{dir}{}", rng.range(1, size.max_distance))
+        })
+        .collect())
+}
+
+// A single line of non-overlapping ID ranges like `11-22,95-115`
+#[derive(Clone, Debug)]
+pub struct Day02Size {
+    pub ranges: usize,
+    pub max_digits: usize,
+    pub max_width: i64,
+}
+
+impl Default for Day02Size {
+    fn default() -> Self {
+        Day02Size {
+            ranges: 35,
+            max_digits: 10,
+            max_width: 100_000,
+        }
+    }
+}
+
+impl Day02Size {
+    pub fn from_args(args: &Args) -> Self {
+        args.expect_params(&["ranges", "max_digits", "max_width"]);
+        let default = Self::default();
+        Day02Size {
+            ranges: args.param("ranges", default.ranges),
+            max_digits: args.param("max_digits", default.max_digits),
+            max_width: args.param("max_width", default.max_width),
+        }
+    }
+}
+
+pub fn day02(rng: &mut Rng, size: &Day02Size) -> Result<Vec<String>, String> {
+    at_least("ranges", size.ranges as i128, 1)?;
+    at_least("max_digits", size.max_digits as i128, 1)?;
+    at_most("max_digits", size.max_digits as i128, 18)?;
+    at_least("max_width", size.max_width as i128, 0)?;
+    at_most("max_width", size.max_width as i128, 10i128.pow(18))?;
+    let mut ranges: Vec<(i64, i64)> = Vec::new();
+    let mut attempts = 0;
+    while ranges.len() < size.ranges && attempts < size.ranges.saturating_mul(100) {
+        attempts += 1;
+        let digits = rng.range(1, size.max_digits as i64) as u32;
+        let low = if digits == 1 {
+            1
+        } else {
+            10i64.pow(digits - 1)
+        };
+        let start = rng.range(low, 10i64.pow(digits) - 1);
+        let end = start + rng.range(0, size.max_width);
+        if ranges.iter().all(|&(s, e)| end < s || start > e) {
+            ranges.push((start, end));
+        }
+    }
+    rng.shuffle(&mut ranges);
+    Ok(vec![
+        ranges
+            .iter()
+            .map(|(start, end)| format!("{start}-{end}"))
+            .collect::<Vec<_>>()
+            .join(","),
+    ])
+}
+
+// Battery banks of digits 1-9
+#[derive(Clone, Debug)]
+pub struct Day03Size {
+    pub banks: usize,
+    pub length: usize,
+}
+
+impl Default for Day03Size {
+    fn default() -> Self {
+        Day03Size {
+            banks: 200,
+            length: 100,
+        }
+    }
+}
+
+impl Day03Size {
+    pub fn from_args(args: &Args) -> Self {
+        args.expect_params(&["banks", "length"]);
+        let default = Self::default();
+        Day03Size {
+            banks: args.param("banks", default.banks),
+            length: args.param("length", default.length),
+        }
+    }
+}
+
+pub fn day03(rng: &mut Rng, size: &Day03Size) -> Result<Vec<String>, String> {
+    at_least("length", size.length as i128, 2)?;
+    Ok((0..size.banks)
+        .map(|_| nonzero_digits(rng, size.length))
+        .collect())
+}
+
+// Grid of paper rolls `@` and empty floor `.`
+#[derive(Clone, Debug)]
+pub struct Day04Size {
+    pub width: usize,
+    pub height: usize,
+    pub density: f64,
+}
+
+impl Default for Day04Size {
+    fn default() -> Self {
+        Day04Size {
+            width: 135,
+            height: 135,
+            density: 0.7,
+        }
+    }
+}
+
+impl Day04Size {
+    pub fn from_args(args: &Args) -> Self {
+        args.expect_params(&["width", "height", "density"]);
+        let default = Self::default();
+        Day04Size {
+            width: args.param("width", default.width),
+            height: args.param("height", default.height),
+            density: args.param("density", default.density),
+        }
+    }
+}
+
+pub fn day04(rng: &mut Rng, size: &Day04Size) -> Result<Vec<String>, String> {
+    Ok((0..size.height)
+        .map(|_| {
+            (0..size.width)
+                .map(|_| if rng.chance(size.density) { '@' } else { '.' })
+                .collect()
+        })
+        .collect())
+}
+
+// Fresh ingredient ranges, an empty line and the available ingredient IDs
+#[derive(Clone, Debug)]
+pub struct Day05Size {
+    pub ranges: usize,
+    pub ids: usize,
+    pub max_id: i64,
+    pub max_width: i64,
+}
+
+impl Default for Day05Size {
+    fn default() -> Self {
+        Day05Size {
+            ranges: 180,
+            ids: 1000,
+            max_id: 500_000_000_000_000,
+            max_width: 20_000_000_000_000,
+        }
+    }
+}
+
+impl Day05Size {
+    pub fn from_args(args: &Args) -> Self {
+        args.expect_params(&["ranges", "ids", "max_id", "max_width"]);
+        let default = Self::default();
+        Day05Size {
+            ranges: args.param("ranges", default.ranges),
+            ids: args.param("ids", default.ids),
+            max_id: args.param("max_id", default.max_id),
+            max_width: args.param("max_width", default.max_width),
+        }
+    }
+}
+
+pub fn day05(rng: &mut Rng, size: &Day05Size) -> Result<Vec<String>, String> {
+    at_least("max_id", size.max_id as i128, 1)?;
+    at_least("max_width", size.max_width as i128, 0)?;
+    at_most(
+        "max_width",
+        size.max_width as i128,
+        (i64::MAX - size.max_id) as i128,
+    )?;
+    let ranges = (0..size.ranges)
+        .map(|_| {
+            let start = rng.range(1, size.max_id);
+            (
+                start,
+                (start + rng.range(0, size.max_width)).min(size.max_id),
+            )
+        })
+        .collect::<Vec<_>>();
+    let ids = (0..size.ids).map(|_| {
+        // Pick half of the IDs near a range so both outcomes are common
+        if !ranges.is_empty() && rng.chance(0.5) {
+            let (start, end) = ranges[rng.index(ranges.len())];
+            rng.range(start, end)
+        } else {
+            rng.range(1, size.max_id)
+        }
+    });
+    let mut lines = ranges
+        .iter()
+        .map(|(start, end)| format!("{start}-{end}"))
+        .collect::<Vec<_>>();
+    lines.push(String::new());
+    lines.extend(ids.map(|id| id.to_string()));
+    Ok(lines)
+}
+
+// Worksheet of column problems, with the operators on the last row
+#[derive(Clone, Debug)]
+pub struct Day06Size {
+    pub problems: usize,
+    pub rows: usize,
+    pub max_digits: usize,
+}
+
+impl Default for Day06Size {
+    fn default() -> Self {
+        Day06Size {
+            problems: 1000,
+            rows: 4,
+            max_digits: 4,
+        }
+    }
+}
+
+impl Day06Size {
+    pub fn from_args(args: &Args) -> Self {
+        args.expect_params(&["problems", "rows", "max_digits"]);
+        let default = Self::default();
+        Day06Size {
+            problems: args.param("problems", default.problems),
+            rows: args.param("rows", default.rows),
+            max_digits: args.param("max_digits", default.max_digits),
+        }
+    }
+}
+
+pub fn day06(rng: &mut Rng, size: &Day06Size) -> Result<Vec<String>, String> {
+    at_least("problems", size.problems as i128, 1)?;
+    at_least("rows", size.rows as i128, 1)?;
+    at_least("max_digits", size.max_digits as i128, 1)?;
+    let mut lines = vec![String::new(); size.rows + 1];
+    for p in 0..size.problems {
+        let mut numbers = (0..size.rows)
+            .map(|_| {
+                let digits = rng.range(1, size.max_digits as i64) as usize;
+                nonzero_digits(rng, digits)
+            })
+            .collect::<Vec<_>>();
+        // Lengths are sorted, like in the puzzle, so no digit column has a gap in the middle
+        numbers.sort_by_key(|n| n.len());
+        if rng.chance(0.5) {
+            numbers.reverse();
+        }
+        let width = numbers.iter().map(|n| n.len()).max().unwrap_or(1);
+        // Numbers within one problem share their alignment
+        let left = rng.chance(0.5);
+        let op = if rng.chance(0.5) { '*' } else { '+' };
+        for (row, number) in numbers.iter().enumerate() {
+            if p > 0 {
+                lines[row].push(' ');
+            }
+            if left {
+                lines[row].push_str(&format!("{number:<width$}"));
+            } else {
+                lines[row].push_str(&format!("{number:>width$}"));
+            }
+        }
+        if p > 0 {
+            lines[size.rows].push(' ');
+        }
+        lines[size.rows].push_str(&format!("{op:<width$}"));
+    }
+    Ok(lines)
+}
+
+// Tachyon manifold with the source `S` on the first row and splitters `^` on every other row
+#[derive(Clone, Debug)]
+pub struct Day07Size {
+    pub width: usize,
+    pub height: usize,
+    pub density: f64,
+}
+
+impl Default for Day07Size {
+    fn default() -> Self {
+        Day07Size {
+            width: 141,
+            height: 142,
+            density: 0.3,
+        }
+    }
+}
+
+impl Day07Size {
+    pub fn from_args(args: &Args) -> Self {
+        args.expect_params(&["width", "height", "density"]);
+        let default = Self::default();
+        Day07Size {
+            width: args.param("width", default.width),
+            height: args.param("height", default.height),
+            density: args.param("density", default.density),
+        }
+    }
+}
+
+pub fn day07(rng: &mut Rng, size: &Day07Size) -> Result<Vec<String>, String> {
+    at_least("width", size.width as i128, 1)?;
+    at_least("height", size.height as i128, 1)?;
+    Ok((0..size.height)
+        .map(|y| {
+            let mut row = vec!['.'; size.width];
+            if y == 0 {
+                row[size.width / 2] = 'S';
+            } else if y % 2 == 0 && y + 1 < size.height {
+                // Splitters are never adjacent or on the edges, like in the puzzle
+                let mut x = 1;
+                while x + 1 < size.width {
+                    if rng.chance(size.density) {
+                        row[x] = '^';
+                        x += 1;
+                    }
+                    x += 1;
+                }
+            }
+            row.into_iter().collect()
+        })
+        .collect())
+}
+
+// Junction box positions `x,y,z`
+#[derive(Clone, Debug)]
+pub struct Day08Size {
+    pub boxes: usize,
+    pub max_coord: i64,
+}
+
+impl Default for Day08Size {
+    fn default() -> Self {
+        Day08Size {
+            boxes: 1000,
+            max_coord: 100_000,
+        }
+    }
+}
+
+impl Day08Size {
+    pub fn from_args(args: &Args) -> Self {
+        args.expect_params(&["boxes", "max_coord"]);
+        let default = Self::default();
+        Day08Size {
+            boxes: args.param("boxes", default.boxes),
+            max_coord: args.param("max_coord", default.max_coord),
+        }
+    }
+}
+
+pub fn day08(rng: &mut Rng, size: &Day08Size) -> Result<Vec<String>, String> {
+    at_least("boxes", size.boxes as i128, 1)?;
+    at_least("max_coord", size.max_coord as i128, 0)?;
+    Ok((0..size.boxes)
+        .map(|_| {
+            let x = rng.range(0, size.max_coord);
+            let y = rng.range(0, size.max_coord);
+            let z = rng.range(0, size.max_coord);
+            format!("{x},{y},{z}")
+        })
+        .collect())
+}
+
+// Red tiles `x,y` forming the corners of a rectilinear polygon, generated as
+// a histogram of columns standing on a common base line. Coordinates are all
+// even, so parallel edges are never exactly one tile apart. The solver treats
+// the loop as a continuous polygon and relies on that, like the real input.
+#[derive(Clone, Debug)]
+pub struct Day09Size {
+    pub columns: usize,
+    pub max_coord: i64,
+}
+
+impl Default for Day09Size {
+    fn default() -> Self {
+        Day09Size {
+            columns: 250,
+            max_coord: 100_000,
+        }
+    }
+}
+
+impl Day09Size {
+    pub fn from_args(args: &Args) -> Self {
+        args.expect_params(&["columns", "max_coord"]);
+        let default = Self::default();
+        Day09Size {
+            columns: args.param("columns", default.columns),
+            max_coord: args.param("max_coord", default.max_coord),
+        }
+    }
+}
+
+pub fn day09(rng: &mut Rng, size: &Day09Size) -> Result<Vec<String>, String> {
+    at_least("max_coord", size.max_coord as i128, 2)?;
+    // Picked on a grid of half the size, then doubled
+    let half = size.max_coord / 2;
+    let columns = size.columns.clamp(1, half as usize);
+    let mut xs = HashSet::new();
+    while xs.len() < columns + 1 {
+        xs.insert(rng.range(0, half) * 2);
+    }
+    let mut xs = xs.into_iter().collect::<Vec<_>>();
+    xs.sort();
+    let base = half * 2;
+    let mut heights: Vec<i64> = Vec::new();
+    for _ in 0..columns {
+        let mut h = rng.range(0, half - 1) * 2;
+        // Neighbouring columns need different heights to keep every vertex a corner
+        while heights.last() == Some(&h) {
+            h = rng.range(0, half - 1) * 2;
+        }
+        heights.push(h);
+    }
+    let mut corners = vec![(xs[0], base)];
+    for (i, h) in heights.iter().enumerate() {
+        corners.push((xs[i], *h));
+        corners.push((xs[i + 1], *h));
+    }
+    corners.push((xs[columns], base));
+    Ok(corners.iter().map(|(x, y)| format!("{x},{y}")).collect())
+}
+
+// Machines with indicator lights, buttons and joltage requirements, all
+// generated from a known set of presses so every machine is solvable
+#[derive(Clone, Debug)]
+pub struct Day10Size {
+    pub machines: usize,
+    pub max_lights: usize,
+    pub max_buttons: usize,
+    pub max_presses: i64,
+}
+
+impl Default for Day10Size {
+    fn default() -> Self {
+        Day10Size {
+            machines: 150,
+            max_lights: 10,
+            max_buttons: 13,
+            max_presses: 20,
+        }
+    }
+}
+
+impl Day10Size {
+    pub fn from_args(args: &Args) -> Self {
+        args.expect_params(&["machines", "max_lights", "max_buttons", "max_presses"]);
+        let default = Self::default();
+        Day10Size {
+            machines: args.param("machines", default.machines),
+            max_lights: args.param("max_lights", default.max_lights),
+            max_buttons: args.param("max_buttons", default.max_buttons),
+            max_presses: args.param("max_presses", default.max_presses),
+        }
+    }
+}
+
+pub fn day10(rng: &mut Rng, size: &Day10Size) -> Result<Vec<String>, String> {
+    at_least("max_lights", size.max_lights as i128, 1)?;
+    at_least("max_buttons", size.max_buttons as i128, 1)?;
+    at_least("max_presses", size.max_presses as i128, 0)?;
+    // Joltages add up the presses of every button
+    at_most(
+        "max_presses",
+        size.max_presses as i128,
+        i64::MAX as i128 / size.max_buttons as i128,
+    )?;
+    Ok((0..size.machines)
+        .map(|_| {
+            let lights = rng.range(1, size.max_lights as i64) as usize;
+            let button_count = rng.range(1, size.max_buttons as i64) as usize;
+            let buttons = (0..button_count)
+                .map(|_| {
+                    let mut wiring = (0..lights).filter(|_| rng.chance(0.4)).collect::<Vec<_>>();
+                    if wiring.is_empty() {
+                        wiring.push(rng.index(lights));
+                    }
+                    wiring
+                })
+                .collect::<Vec<_>>();
+            let mut grid = vec![false; lights];
+            let mut joltages = vec![0; lights];
+            for button in &buttons {
+                let presses = rng.range(0, size.max_presses);
+                let toggled = rng.chance(0.5);
+                for &light in button {
+                    joltages[light] += presses;
+                    if toggled {
+                        grid[light] = !grid[light];
+                    }
+                }
+            }
+            let grid = grid
+                .iter()
+                .map(|&on| if on { '#' } else { '.' })
+                .collect::<String>();
+            let buttons = buttons
+                .iter()
+                .map(|b| {
+                    format!(
+                        "({})",
+                        b.iter()
+                            .map(|l| l.to_string())
+                            .collect::<Vec<_>>()
+                            .join(",")
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            let joltages = joltages
+                .iter()
+                .map(|j| j.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            format!("[{grid}] {buttons} {{{joltages}}}")
+        })
+        .collect())
+}
+
+// Layered device graph from `svr` to the final `out`, with `you`, `fft` and
+// `dac` placed in the first, middle and last quarter of the layers
+#[derive(Clone, Debug)]
+pub struct Day11Size {
+    pub devices: usize,
+    pub layers: usize,
+    pub max_outputs: usize,
+}
+
+impl Default for Day11Size {
+    fn default() -> Self {
+        Day11Size {
+            devices: 600,
+            layers: 16,
+            max_outputs: 3,
+        }
+    }
+}
+
+impl Day11Size {
+    pub fn from_args(args: &Args) -> Self {
+        args.expect_params(&["devices", "layers", "max_outputs"]);
+        let default = Self::default();
+        Day11Size {
+            devices: args.param("devices", default.devices),
+            layers: args.param("layers", default.layers),
+            max_outputs: args.param("max_outputs", default.max_outputs),
+        }
+    }
+}
+
+pub fn day11(rng: &mut Rng, size: &Day11Size) -> Result<Vec<String>, String> {
+    at_least("max_outputs", size.max_outputs as i128, 1)?;
+    // Devices get distinct three letter names, and `out` isn't a device
+    let names = 26i128.pow(3) - 1;
+    at_most("layers", size.layers as i128, names - 3)?;
+    let layer_count = size.layers.max(4);
+    let devices = size.devices.max(layer_count + 3);
+    at_most("devices", devices as i128, names)?;
+    let mut used: HashSet<String> = ["svr", "you", "dac", "fft", "out"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let mut layers: Vec<Vec<String>> = vec![Vec::new(); layer_count];
+    layers[0].push("svr".to_string());
+    layers[rng.range(1, layer_count as i64 / 4) as usize].push("you".to_string());
+    layers[layer_count / 2].push("fft".to_string());
+    layers[layer_count - 1 - rng.index(layer_count / 4)].push("dac".to_string());
+    // Every other layer gets at least one device, the rest are spread randomly
+    let mut remaining = devices - 4;
+    for layer in layers.iter_mut().skip(1) {
+        if layer.is_empty() {
+            layer.push(String::new());
+            remaining -= 1;
+        }
+    }
+    for _ in 0..remaining {
+        layers[rng.range(1, layer_count as i64 - 1) as usize].push(String::new());
+    }
+    for name in layers.iter_mut().flatten().filter(|n| n.is_empty()) {
+        while name.is_empty() || !used.insert(name.clone()) {
+            *name = (0..3)
+                .map(|_| char::from(b'a' + rng.range(0, 25) as u8))
+                .collect();
+        }
+    }
+
+    // One device per layer forms a path through `svr`, `fft` and `dac`, so part 2 has an answer
+    let spine = layers
+        .iter()
+        .map(|layer| {
+            match layer
+                .iter()
+                .find(|n| ["svr", "fft", "dac"].contains(&n.as_str()))
+            {
+                Some(name) => name.clone(),
+                None => layer[rng.index(layer.len())].clone(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let out = vec!["out".to_string()];
+    let mut lines = Vec::new();
+    for (i, layer) in layers.iter().enumerate() {
+        let next = layers.get(i + 1).unwrap_or(&out);
+        for name in layer {
+            let count = rng.range(1, size.max_outputs.min(next.len()) as i64) as usize;
+            let mut outputs = next.clone();
+            rng.shuffle(&mut outputs);
+            outputs.truncate(count);
+            if *name == spine[i] && i + 1 < spine.len() && !outputs.contains(&spine[i + 1]) {
+                outputs[0] = spine[i + 1].clone();
+            }
+            lines.push(format!("{name}: {}", outputs.join(" ")));
+        }
+    }
+    rng.shuffle(&mut lines);
+    Ok(lines)
+}
+
+// Present shapes (3x3, seven cells each) followed by regions `WxH: counts`.
+// Like in the puzzle, each region either fits its presents in a plain 3x3
+// layout or has less area than the presents need.
+#[derive(Clone, Debug)]
+pub struct Day12Size {
+    pub shapes: usize,
+    pub regions: usize,
+    pub max_side: i64,
+}
+
+impl Default for Day12Size {
+    fn default() -> Self {
+        Day12Size {
+            shapes: 6,
+            regions: 1000,
+            max_side: 50,
+        }
+    }
+}
+
+impl Day12Size {
+    pub fn from_args(args: &Args) -> Self {
+        args.expect_params(&["shapes", "regions", "max_side"]);
+        let default = Self::default();
+        Day12Size {
+            shapes: args.param("shapes", default.shapes),
+            regions: args.param("regions", default.regions),
+            max_side: args.param("max_side", default.max_side),
+        }
+    }
+}
+
+pub fn day12(rng: &mut Rng, size: &Day12Size) -> Result<Vec<String>, String> {
+    at_least("shapes", size.shapes as i128, 1)?;
+    at_least("max_side", size.max_side as i128, 3)?;
+    // Areas are computed as width * height
+    at_most("max_side", size.max_side as i128, i64::MAX.isqrt() as i128)?;
+    let mut lines = Vec::new();
+    for i in 0..size.shapes {
+        let mut cells = ['#'; 9];
+        let mut holes = 0;
+        while holes < 2 {
+            let c = rng.index(9);
+            if cells[c] == '#' {
+                cells[c] = '.';
+                holes += 1;
+            }
+        }
+        lines.push(format!("{i}:"));
+        lines.extend(cells.chunks(3).map(|row| row.iter().collect::<String>()));
+        lines.push(String::new());
+    }
+    for _ in 0..size.regions {
+        let width = rng.range(3, size.max_side);
+        let height = rng.range(3, size.max_side);
+        let easy = (width / 3) * (height / 3);
+        let total = if rng.chance(0.5) {
+            rng.range(0, easy)
+        } else {
+            let needed = width * height / 7 + 1;
+            rng.range(needed, needed + easy / 4)
+        };
+        let mut counts = vec![0; size.shapes];
+        for _ in 0..total {
+            counts[rng.index(size.shapes)] += 1;
+        }
+        let counts = counts
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        lines.push(format!("{width}x{height}: {counts}"));
+    }
+    Ok(lines)
+}
+
+// Generate an input for the given day, with sizes taken from `--param` overrides
+pub fn for_day(day: u32, rng: &mut Rng, args: &Args) -> Result<Vec<String>, String> {
+    match day {
+        1 => day01(rng, &Day01Size::from_args(args)),
+        2 => day02(rng, &Day02Size::from_args(args)),
+        3 => day03(rng, &Day03Size::from_args(args)),
+        4 => day04(rng, &Day04Size::from_args(args)),
+        5 => day05(rng, &Day05Size::from_args(args)),
+        6 => day06(rng, &Day06Size::from_args(args)),
+        7 => day07(rng, &Day07Size::from_args(args)),
+        8 => day08(rng, &Day08Size::from_args(args)),
+        9 => day09(rng, &Day09Size::from_args(args)),
+        10 => day10(rng, &Day10Size::from_args(args)),
+        11 => day11(rng, &Day11Size::from_args(args)),
+        12 => day12(rng, &Day12Size::from_args(args)),
+        _ => Err(format!("No generator for day {day}")),
+    }
+}
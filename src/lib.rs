@@ -1 +1,5 @@
+pub mod check;
 pub mod common;
+pub mod generate;
+pub mod reference;
+pub mod render;
@@ -0,0 +1,29 @@
+// Turn the dial one click at a time
+
+pub fn part1(rotations: &[i64], start: i64, size: i64) -> i64 {
+    let mut pos = start;
+    let mut zeros = 0;
+    for &rotation in rotations {
+        for _ in 0..rotation.abs() {
+            pos = (pos + rotation.signum()).rem_euclid(size);
+        }
+        if pos == 0 {
+            zeros += 1;
+        }
+    }
+    zeros
+}
+
+pub fn part2(rotations: &[i64], start: i64, size: i64) -> i64 {
+    let mut pos = start;
+    let mut zeros = 0;
+    for &rotation in rotations {
+        for _ in 0..rotation.abs() {
+            pos = (pos + rotation.signum()).rem_euclid(size);
+            if pos == 0 {
+                zeros += 1;
+            }
+        }
+    }
+    zeros
+}
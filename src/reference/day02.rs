@@ -0,0 +1,25 @@
+use std::collections::HashSet;
+
+// Check every ID in every range
+
+// Whether the ID is some digit sequence repeated exactly `times` times
+fn repeats(id: i64, times: usize) -> bool {
+    let s = id.to_string();
+    s.len().is_multiple_of(times) && s == s[..s.len() / times].repeat(times)
+}
+
+pub fn part1(ranges: &[(i64, i64)]) -> i64 {
+    ranges
+        .iter()
+        .map(|&(start, end)| (start..=end).filter(|&id| repeats(id, 2)).sum::<i64>())
+        .sum()
+}
+
+pub fn part2(ranges: &[(i64, i64)]) -> i64 {
+    let invalid: HashSet<i64> = ranges
+        .iter()
+        .flat_map(|&(start, end)| start..=end)
+        .filter(|&id| (2..=id.to_string().len()).any(|times| repeats(id, times)))
+        .collect();
+    invalid.iter().sum()
+}
@@ -0,0 +1,20 @@
+use itertools::Itertools;
+
+// Try every choice of batteries
+
+pub fn max_joltage(bank: &str, digits: usize) -> i64 {
+    let bank = bank.as_bytes();
+    (0..bank.len())
+        .combinations(digits)
+        .map(|chosen| {
+            chosen
+                .iter()
+                .fold(0, |acc, &i| acc * 10 + (bank[i] - b'0') as i64)
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+pub fn total(banks: &[String], digits: usize) -> i64 {
+    banks.iter().map(|bank| max_joltage(bank, digits)).sum()
+}
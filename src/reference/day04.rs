@@ -0,0 +1,45 @@
+// Recount every roll's neighbors from scratch on every round
+
+fn accessible(grid: &[Vec<char>], threshold: usize) -> Vec<(usize, usize)> {
+    let mut result = Vec::new();
+    for y in 0..grid.len() {
+        for x in 0..grid[y].len() {
+            if grid[y][x] != '@' {
+                continue;
+            }
+            let mut count = 0;
+            for ny in y.saturating_sub(1)..=y + 1 {
+                for nx in x.saturating_sub(1)..=x + 1 {
+                    if (nx, ny) != (x, y) && grid.get(ny).and_then(|row| row.get(nx)) == Some(&'@')
+                    {
+                        count += 1;
+                    }
+                }
+            }
+            if count < threshold {
+                result.push((x, y));
+            }
+        }
+    }
+    result
+}
+
+pub fn part1(grid: &[String], threshold: usize) -> i64 {
+    let grid: Vec<Vec<char>> = grid.iter().map(|row| row.chars().collect()).collect();
+    accessible(&grid, threshold).len() as i64
+}
+
+pub fn part2(grid: &[String], threshold: usize) -> i64 {
+    let mut grid: Vec<Vec<char>> = grid.iter().map(|row| row.chars().collect()).collect();
+    let mut removed = 0;
+    loop {
+        let round = accessible(&grid, threshold);
+        if round.is_empty() {
+            return removed;
+        }
+        removed += round.len() as i64;
+        for (x, y) in round {
+            grid[y][x] = '.';
+        }
+    }
+}
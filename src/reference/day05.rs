@@ -0,0 +1,17 @@
+use std::collections::HashSet;
+
+// List every fresh ID one by one
+
+pub fn part1(ranges: &[(i64, i64)], ids: &[i64]) -> i64 {
+    ids.iter()
+        .filter(|&&id| ranges.iter().any(|&(start, end)| start <= id && id <= end))
+        .count() as i64
+}
+
+pub fn part2(ranges: &[(i64, i64)]) -> i64 {
+    let fresh: HashSet<i64> = ranges
+        .iter()
+        .flat_map(|&(start, end)| start..=end)
+        .collect();
+    fresh.len() as i64
+}
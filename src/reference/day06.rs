@@ -0,0 +1,62 @@
+// Cut the worksheet into problem blocks at the all-space columns and read
+// each block directly
+
+// Character grid of each problem block, in order
+fn blocks(grid: &[Vec<char>]) -> Vec<Vec<Vec<char>>> {
+    let width = grid.iter().map(|row| row.len()).max().unwrap_or(0);
+    let cell = |x: usize, y: usize| *grid[y].get(x).unwrap_or(&' ');
+    let mut blocks = Vec::new();
+    let mut current: Vec<usize> = Vec::new();
+    for x in 0..=width {
+        if x < width && (0..grid.len()).any(|y| cell(x, y) != ' ') {
+            current.push(x);
+        } else if !current.is_empty() {
+            blocks.push(
+                (0..grid.len())
+                    .map(|y| current.iter().map(|&x| cell(x, y)).collect())
+                    .collect(),
+            );
+            current.clear();
+        }
+    }
+    blocks
+}
+
+fn solve(symbols: &[String], numbers: Vec<Vec<i64>>) -> i64 {
+    symbols
+        .iter()
+        .zip(numbers)
+        .map(|(symbol, numbers)| match symbol.as_str() {
+            "*" => numbers.iter().product::<i64>(),
+            _ => numbers.iter().sum::<i64>(),
+        })
+        .sum()
+}
+
+fn read(digits: impl Iterator<Item = char>) -> i64 {
+    digits
+        .filter(|c| c.is_ascii_digit())
+        .fold(0, |acc, c| acc * 10 + c.to_digit(10).unwrap() as i64)
+}
+
+// Each row of a block is a number
+pub fn part1(symbols: &[String], grid: &[Vec<char>]) -> i64 {
+    let numbers = blocks(grid)
+        .iter()
+        .map(|block| block.iter().map(|row| read(row.iter().copied())).collect())
+        .collect();
+    solve(symbols, numbers)
+}
+
+// Each column of a block is a number, read top to bottom
+pub fn part2(symbols: &[String], grid: &[Vec<char>]) -> i64 {
+    let numbers = blocks(grid)
+        .iter()
+        .map(|block| {
+            (0..block[0].len())
+                .map(|x| read(block.iter().map(|row| row[x])))
+                .collect()
+        })
+        .collect();
+    solve(symbols, numbers)
+}
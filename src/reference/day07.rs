@@ -0,0 +1,51 @@
+use std::collections::HashSet;
+
+// Move the beams down one row at a time, and follow every timeline separately
+
+fn start(grid: &[Vec<char>]) -> usize {
+    grid[0].iter().position(|&c| c == 'S').unwrap()
+}
+
+// Columns a beam continues in after reaching row `y` in column `x`
+fn next_columns(grid: &[Vec<char>], x: usize, y: usize) -> Vec<usize> {
+    if grid[y][x] != '^' {
+        return vec![x];
+    }
+    let mut result = Vec::new();
+    if x > 0 {
+        result.push(x - 1);
+    }
+    if x + 1 < grid[y].len() {
+        result.push(x + 1);
+    }
+    result
+}
+
+pub fn part1(grid: &[Vec<char>]) -> i64 {
+    let mut beams = HashSet::from([start(grid)]);
+    let mut splitters = HashSet::new();
+    for y in 1..grid.len() {
+        let mut next = HashSet::new();
+        for &x in &beams {
+            if grid[y][x] == '^' {
+                splitters.insert((x, y));
+            }
+            next.extend(next_columns(grid, x, y));
+        }
+        beams = next;
+    }
+    splitters.len() as i64
+}
+
+pub fn part2(grid: &[Vec<char>]) -> i64 {
+    fn timelines(grid: &[Vec<char>], x: usize, y: usize) -> i64 {
+        if y == grid.len() {
+            return 1;
+        }
+        next_columns(grid, x, y)
+            .into_iter()
+            .map(|nx| timelines(grid, nx, y + 1))
+            .sum()
+    }
+    timelines(grid, start(grid), 1)
+}
@@ -0,0 +1,62 @@
+// Recompute the circuits with a graph search after every connection. The
+// pair order is the puzzle solution's: by squared distance, ties in input
+// order.
+
+fn pairs(boxes: &[(i64, i64, i64)]) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+    for i in 0..boxes.len() {
+        for j in i + 1..boxes.len() {
+            pairs.push((i, j));
+        }
+    }
+    pairs.sort_by_key(|&(i, j)| {
+        let (a, b) = (boxes[i], boxes[j]);
+        (a.0 - b.0).pow(2) + (a.1 - b.1).pow(2) + (a.2 - b.2).pow(2)
+    });
+    pairs
+}
+
+// Sizes of the connected components, using only the given connections
+fn circuits(count: usize, connections: &[(usize, usize)]) -> Vec<usize> {
+    let mut seen = vec![false; count];
+    let mut sizes = Vec::new();
+    for start in 0..count {
+        if seen[start] {
+            continue;
+        }
+        seen[start] = true;
+        let mut stack = vec![start];
+        let mut size = 0;
+        while let Some(node) = stack.pop() {
+            size += 1;
+            for &(a, b) in connections {
+                for (from, to) in [(a, b), (b, a)] {
+                    if from == node && !seen[to] {
+                        seen[to] = true;
+                        stack.push(to);
+                    }
+                }
+            }
+        }
+        sizes.push(size);
+    }
+    sizes
+}
+
+pub fn part1(boxes: &[(i64, i64, i64)], connections: usize) -> i64 {
+    let pairs = pairs(boxes);
+    let mut sizes = circuits(boxes.len(), &pairs[..connections.min(pairs.len())]);
+    sizes.sort_by(|a, b| b.cmp(a));
+    sizes.iter().take(3).product::<usize>() as i64
+}
+
+pub fn part2(boxes: &[(i64, i64, i64)]) -> i64 {
+    let pairs = pairs(boxes);
+    for n in 1..=pairs.len() {
+        if circuits(boxes.len(), &pairs[..n]).len() == 1 {
+            let (i, j) = pairs[n - 1];
+            return boxes[i].0 * boxes[j].0;
+        }
+    }
+    0
+}
@@ -0,0 +1,68 @@
+use std::collections::HashSet;
+
+// Mark every red or green tile, then check every tile of every rectangle
+
+pub fn part1(red: &[(i64, i64)]) -> i64 {
+    let mut best = 0;
+    for &(x1, y1) in red {
+        for &(x2, y2) in red {
+            best = best.max(((x1 - x2).abs() + 1) * ((y1 - y2).abs() + 1));
+        }
+    }
+    best
+}
+
+fn colored_tiles(red: &[(i64, i64)]) -> HashSet<(i64, i64)> {
+    let edges = red
+        .iter()
+        .zip(red.iter().cycle().skip(1))
+        .map(|(&a, &b)| (a, b))
+        .collect::<Vec<_>>();
+    let mut tiles = HashSet::new();
+    // Tiles on the loop itself
+    for &((x1, y1), (x2, y2)) in &edges {
+        for x in x1.min(x2)..=x1.max(x2) {
+            for y in y1.min(y2)..=y1.max(y2) {
+                tiles.insert((x, y));
+            }
+        }
+    }
+    // Tiles inside the loop, by counting vertical edges crossed towards the right
+    let (min_x, max_x) = (
+        red.iter().map(|p| p.0).min().unwrap(),
+        red.iter().map(|p| p.0).max().unwrap(),
+    );
+    let (min_y, max_y) = (
+        red.iter().map(|p| p.1).min().unwrap(),
+        red.iter().map(|p| p.1).max().unwrap(),
+    );
+    for x in min_x..=max_x {
+        for y in min_y..=max_y {
+            let crossings = edges
+                .iter()
+                .filter(|&&((x1, y1), (x2, y2))| {
+                    x1 == x2 && x1 > x && y1.min(y2) <= y && y < y1.max(y2)
+                })
+                .count();
+            if crossings % 2 == 1 {
+                tiles.insert((x, y));
+            }
+        }
+    }
+    tiles
+}
+
+pub fn part2(red: &[(i64, i64)]) -> i64 {
+    let tiles = colored_tiles(red);
+    let mut best = 0;
+    for &(x1, y1) in red {
+        for &(x2, y2) in red {
+            let inside = (x1.min(x2)..=x1.max(x2))
+                .all(|x| (y1.min(y2)..=y1.max(y2)).all(|y| tiles.contains(&(x, y))));
+            if inside {
+                best = best.max(((x1 - x2).abs() + 1) * ((y1 - y2).abs() + 1));
+            }
+        }
+    }
+    best
+}
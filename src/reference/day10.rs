@@ -0,0 +1,73 @@
+use std::collections::{HashSet, VecDeque};
+
+// Breadth-first search over machine states, one button press at a time
+
+type Machine = (Vec<bool>, Vec<Vec<usize>>, Vec<usize>);
+
+fn fewest_presses<S: Clone + Eq + std::hash::Hash>(
+    start: S,
+    target: &S,
+    buttons: &[Vec<usize>],
+    press: impl Fn(&S, &[usize]) -> Option<S>,
+) -> Option<i64> {
+    let mut seen = HashSet::from([start.clone()]);
+    let mut queue = VecDeque::from([(start, 0)]);
+    while let Some((state, presses)) = queue.pop_front() {
+        if &state == target {
+            return Some(presses);
+        }
+        for button in buttons {
+            if let Some(next) = press(&state, button)
+                && seen.insert(next.clone())
+            {
+                queue.push_back((next, presses + 1));
+            }
+        }
+    }
+    None
+}
+
+pub fn part1(machines: &[Machine]) -> i64 {
+    machines
+        .iter()
+        .map(|(lights, buttons, _)| {
+            fewest_presses(
+                vec![false; lights.len()],
+                lights,
+                buttons,
+                |state, button| {
+                    let mut next = state.clone();
+                    for &light in button {
+                        next[light] = !next[light];
+                    }
+                    Some(next)
+                },
+            )
+            .unwrap_or(0)
+        })
+        .sum()
+}
+
+pub fn part2(machines: &[Machine]) -> i64 {
+    machines
+        .iter()
+        .map(|(_, buttons, joltages)| {
+            fewest_presses(
+                vec![0; joltages.len()],
+                joltages,
+                buttons,
+                |state, button| {
+                    let mut next = state.clone();
+                    for &counter in button {
+                        next[counter] += 1;
+                        if next[counter] > joltages[counter] {
+                            return None;
+                        }
+                    }
+                    Some(next)
+                },
+            )
+            .unwrap()
+        })
+        .sum()
+}
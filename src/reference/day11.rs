@@ -0,0 +1,37 @@
+// Walk every path separately
+
+fn walk(
+    edges: &[(String, String)],
+    current: &str,
+    visited: &mut Vec<String>,
+    paths: &mut Vec<Vec<String>>,
+) {
+    visited.push(current.to_string());
+    if current == "out" {
+        paths.push(visited.clone());
+    } else {
+        for (from, to) in edges {
+            if from == current {
+                walk(edges, to, visited, paths);
+            }
+        }
+    }
+    visited.pop();
+}
+
+fn paths(edges: &[(String, String)], start: &str) -> Vec<Vec<String>> {
+    let mut paths = Vec::new();
+    walk(edges, start, &mut Vec::new(), &mut paths);
+    paths
+}
+
+pub fn part1(edges: &[(String, String)]) -> i64 {
+    paths(edges, "you").len() as i64
+}
+
+pub fn part2(edges: &[(String, String)]) -> i64 {
+    paths(edges, "svr")
+        .iter()
+        .filter(|path| path.iter().any(|d| d == "dac") && path.iter().any(|d| d == "fft"))
+        .count() as i64
+}
@@ -0,0 +1,91 @@
+// Try to actually place every present, in every orientation and position
+
+type Shape = Vec<(usize, usize)>;
+
+// Distinct rotations and flips of a shape, as cell offsets
+fn orientations(shape: &[Vec<char>]) -> Vec<Shape> {
+    let mut cells: Shape = Vec::new();
+    for (y, row) in shape.iter().enumerate() {
+        for (x, &c) in row.iter().enumerate() {
+            if c == '#' {
+                cells.push((x, y));
+            }
+        }
+    }
+    let mut result: Vec<Shape> = Vec::new();
+    for flip in [false, true] {
+        let mut current: Vec<(i64, i64)> = cells
+            .iter()
+            .map(|&(x, y)| {
+                if flip {
+                    (-(x as i64), y as i64)
+                } else {
+                    (x as i64, y as i64)
+                }
+            })
+            .collect();
+        for _ in 0..4 {
+            current = current.iter().map(|&(x, y)| (-y, x)).collect();
+            let min_x = current.iter().map(|c| c.0).min().unwrap_or(0);
+            let min_y = current.iter().map(|c| c.1).min().unwrap_or(0);
+            let mut normalized: Shape = current
+                .iter()
+                .map(|&(x, y)| ((x - min_x) as usize, (y - min_y) as usize))
+                .collect();
+            normalized.sort();
+            if !result.contains(&normalized) {
+                result.push(normalized);
+            }
+        }
+    }
+    result
+}
+
+fn place(grid: &mut Vec<Vec<bool>>, presents: &[Vec<Shape>]) -> bool {
+    let Some((first, rest)) = presents.split_first() else {
+        return true;
+    };
+    let (height, width) = (grid.len(), grid[0].len());
+    for shape in first {
+        for y in 0..height {
+            for x in 0..width {
+                let fits = shape
+                    .iter()
+                    .all(|&(dx, dy)| x + dx < width && y + dy < height && !grid[y + dy][x + dx]);
+                if !fits {
+                    continue;
+                }
+                shape
+                    .iter()
+                    .for_each(|&(dx, dy)| grid[y + dy][x + dx] = true);
+                if place(grid, rest) {
+                    return true;
+                }
+                shape
+                    .iter()
+                    .for_each(|&(dx, dy)| grid[y + dy][x + dx] = false);
+            }
+        }
+    }
+    false
+}
+
+pub fn fits(shapes: &[Vec<Vec<char>>], width: usize, height: usize, counts: &[usize]) -> bool {
+    let presents: Vec<Vec<Shape>> = counts
+        .iter()
+        .enumerate()
+        .flat_map(|(i, &count)| vec![orientations(&shapes[i]); count])
+        .collect();
+    let area: usize = presents.iter().map(|p| p[0].len()).sum();
+    if area > width * height {
+        return false;
+    }
+    place(&mut vec![vec![false; width]; height], &presents)
+}
+
+pub fn part1(shapes: &[Vec<Vec<char>>], regions: &[(usize, usize, Vec<usize>)]) -> i64 {
+    regions
+        .iter()
+        .filter(|(width, height, counts)| fits(shapes, *width, *height, counts))
+        .count() as i64
+}
@@ -0,0 +1,141 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use gif::{Encoder, Frame, Repeat};
+
+pub type Rgb = [u8; 3];
+
+pub const BLACK: Rgb = [0, 0, 0];
+pub const WHITE: Rgb = [255, 255, 255];
+pub const GRAY: Rgb = [128, 128, 128];
+
+// Plain RGB image, for drawing puzzle grids one cell per pixel
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, background: Rgb) -> Image {
+        Image {
+            width,
+            height,
+            pixels: vec![background; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Rgb {
+        self.pixels[y * self.width + x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, color: Rgb) {
+        self.pixels[y * self.width + x] = color;
+    }
+
+    // Every pixel as a square of `scale` pixels, so small grids are visible
+    pub fn scaled(&self, scale: usize) -> Image {
+        let mut scaled = Image::new(self.width * scale, self.height * scale, BLACK);
+        for y in 0..scaled.height {
+            for x in 0..scaled.width {
+                scaled.set(x, y, self.get(x / scale, y / scale));
+            }
+        }
+        scaled
+    }
+
+    // Binary PPM (P6), which nearly every image viewer can open
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut ppm = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        ppm.extend(self.pixels.iter().flatten());
+        ppm
+    }
+
+    pub fn write_ppm(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        File::create(path)?.write_all(&self.to_ppm())
+    }
+}
+
+// Color for a value between 0 and 1, from dark blue through green to yellow
+pub fn gradient(t: f64) -> Rgb {
+    const STOPS: [Rgb; 5] = [
+        [68, 1, 84],
+        [59, 82, 139],
+        [33, 145, 140],
+        [94, 201, 98],
+        [253, 231, 37],
+    ];
+    let t = t.clamp(0.0, 1.0) * (STOPS.len() - 1) as f64;
+    let i = (t as usize).min(STOPS.len() - 2);
+    let f = t - i as f64;
+    let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * f).round() as u8;
+    [0, 1, 2].map(|c| mix(STOPS[i][c], STOPS[i + 1][c]))
+}
+
+// Animated GIF of equally sized frames, looping forever, with the delay
+// between frames in hundredths of a second
+pub fn write_gif(path: impl AsRef<Path>, frames: &[Image], delay: u16) -> io::Result<()> {
+    let Some(first) = frames.first() else {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "No frames"));
+    };
+    let (width, height) = match (u16::try_from(first.width), u16::try_from(first.height)) {
+        (Ok(w), Ok(h)) => (w, h),
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Image too large for a GIF",
+            ));
+        }
+    };
+    // Puzzle renders use few colors, so a shared palette usually fits and
+    // avoids quantizing every frame
+    let mut palette: HashMap<Rgb, usize> = HashMap::new();
+    for pixel in frames.iter().flat_map(|f| &f.pixels) {
+        let next = palette.len();
+        palette.entry(*pixel).or_insert(next);
+        if palette.len() > 256 {
+            break;
+        }
+    }
+    let shared = palette.len() <= 256;
+    let mut colors = vec![0; palette.len() * 3];
+    for (color, &i) in &palette {
+        colors[i * 3..i * 3 + 3].copy_from_slice(color);
+    }
+
+    let file = BufWriter::new(File::create(path)?);
+    let global: &[u8] = if shared { &colors } else { &[] };
+    let mut encoder = Encoder::new(file, width, height, global).map_err(io::Error::other)?;
+    encoder
+        .set_repeat(Repeat::Infinite)
+        .map_err(io::Error::other)?;
+    for image in frames {
+        let mut frame = if shared {
+            let indices = image
+                .pixels
+                .iter()
+                .map(|p| palette[p] as u8)
+                .collect::<Vec<_>>();
+            Frame::from_indexed_pixels(width, height, indices, None)
+        } else {
+            let rgb = image.pixels.iter().flatten().copied().collect::<Vec<_>>();
+            Frame::from_rgb_speed(width, height, &rgb, 10)
+        };
+        frame.delay = delay;
+        encoder.write_frame(&frame).map_err(io::Error::other)?;
+    }
+    Ok(())
+}